@@ -1,5 +1,5 @@
 use super::Command;
-use crate::rgl::{Config, ConfigCst, RemoteFilter, Session};
+use crate::rgl::{Config, ConfigCst, Lockfile, RemoteFilter, Session};
 use crate::{info, warn};
 use anyhow::Result;
 use clap::Args;
@@ -19,6 +19,7 @@ impl Command for Add {
     fn dispatch(&self) -> Result<()> {
         let config = Config::load()?;
         let config_cst = ConfigCst::load()?;
+        let mut lockfile = Lockfile::load()?;
         let mut session = Session::lock()?;
         let data_path = config.get_data_path();
 
@@ -26,6 +27,7 @@ impl Command for Add {
             info!("Adding filter <filter>{arg}</>...");
             let (filter_name, remote) = RemoteFilter::parse(arg)?;
             remote.install(&filter_name, Some(&data_path), self.force)?;
+            lockfile.insert(&filter_name, remote.lock(&filter_name)?);
 
             for profile_name in &self.profile {
                 if config_cst.add_filter_to_profile(&filter_name, profile_name) {
@@ -40,6 +42,7 @@ impl Command for Add {
         }
 
         config_cst.save()?;
+        lockfile.save()?;
         session.unlock()
     }
     fn error_context(&self) -> String {
@@ -0,0 +1,242 @@
+use super::Command;
+use crate::fs::{dir_size, rimraf};
+use crate::rgl::{
+    cached_filters, cached_go_builds, cached_repos, format_age, format_size, get_cache_dir,
+    get_repo_cache_dir, get_resolver_cache_dir, get_staging_dir, referenced_filters,
+    resolver_repos,
+};
+use crate::{info, log, success, warn};
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Args, Subcommand};
+use std::{
+    collections::HashSet,
+    fs,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+/// Inspect and clean up the global rgl cache
+#[derive(Args)]
+pub struct Cache {
+    #[command(subcommand)]
+    command: CacheCommand,
+}
+
+#[derive(Subcommand)]
+enum CacheCommand {
+    /// List cached filters, their sizes and the projects that used them
+    #[clap(alias = "ls")]
+    List,
+    /// Remove cached filters, repositories and builds that are no longer used
+    Prune {
+        /// Keep anything used more recently than this, e.g. `12h`, `30d` or `2w`
+        #[arg(long, default_value = "30d", value_parser = parse_duration)]
+        older_than: Duration,
+        /// Only show what would be removed
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Check cached filters for interrupted installs and modified files
+    Verify {
+        /// Remove broken filters, they are reinstalled on the next `rgl get`
+        #[arg(long)]
+        remove: bool,
+    },
+}
+
+impl Command for Cache {
+    fn dispatch(&self) -> Result<()> {
+        match &self.command {
+            CacheCommand::List => list(),
+            CacheCommand::Prune {
+                older_than,
+                dry_run,
+            } => prune(*older_than, *dry_run),
+            CacheCommand::Verify { remove } => verify(*remove),
+        }
+    }
+    fn error_context(&self) -> String {
+        match self.command {
+            CacheCommand::List => "Error listing cache",
+            CacheCommand::Prune { .. } => "Error pruning cache",
+            CacheCommand::Verify { .. } => "Error verifying cache",
+        }
+        .to_owned()
+    }
+}
+
+fn list() -> Result<()> {
+    let mut filters = cached_filters()?;
+    filters.sort_by(|a, b| (&a.url, &a.name, &a.version).cmp(&(&b.url, &b.name, &b.version)));
+    if !filters.is_empty() {
+        log!("<green>Filters:</>");
+    }
+    for filter in &filters {
+        log!(
+            "  {}/{}@{} <cyan>{}</>",
+            filter.url,
+            filter.name,
+            filter.version,
+            format_size(filter.size)
+        );
+        for (project, time) in &filter.projects {
+            log!("    <bright-black>{project} ({})</>", format_age(*time));
+        }
+    }
+    let go_size: u64 = cached_go_builds()?
+        .iter()
+        .map(|(path, _)| dir_size(path))
+        .sum();
+    let sizes = [
+        ("Filters", filters.iter().map(|filter| filter.size).sum()),
+        ("Repositories", dir_size(get_repo_cache_dir()?)),
+        ("Resolvers", dir_size(get_resolver_cache_dir()?)),
+        ("Go builds", go_size),
+    ];
+    log!("<green>Size:</>");
+    for (label, size) in sizes {
+        log!("  {label} <cyan>{}</>", format_size(size));
+    }
+    log!(
+        "  Total <cyan>{}</> <bright-black>{}</>",
+        format_size(dir_size(get_cache_dir()?)),
+        get_cache_dir()?.display()
+    );
+    Ok(())
+}
+
+fn prune(older_than: Duration, dry_run: bool) -> Result<()> {
+    let cutoff = SystemTime::now()
+        .checked_sub(older_than)
+        .context("Invalid duration, `--older-than` is too large")?;
+    let referenced = referenced_filters()?;
+    let mut freed = 0;
+    let mut remove = |path: &Path, label: &str, size: u64| -> Result<()> {
+        match dry_run {
+            true => info!("Would remove {label} <cyan>{}</>", format_size(size)),
+            false => {
+                rimraf(path)?;
+                remove_empty_parents(path)?;
+                info!("Removed {label} <cyan>{}</>", format_size(size));
+            }
+        }
+        freed += size;
+        Ok(())
+    };
+
+    let mut repos_in_use: HashSet<_> = resolver_repos();
+    for filter in cached_filters()? {
+        if referenced.contains(&filter.path) || filter.last_used() > cutoff {
+            repos_in_use.insert(filter.repo_path());
+            continue;
+        }
+        let label = format!(
+            "filter <b>{}/{}@{}</>",
+            filter.url, filter.name, filter.version
+        );
+        remove(&filter.path, &label, filter.size)?;
+    }
+    let repo_dir = get_repo_cache_dir()?;
+    for repo in cached_repos()? {
+        if repos_in_use.contains(&repo) {
+            continue;
+        }
+        let path = repo_dir.join(&repo);
+        let label = format!("repository <b>{}</>", repo.display());
+        remove(&path, &label, dir_size(&path))?;
+    }
+    for (path, last_used) in cached_go_builds()? {
+        if last_used > cutoff {
+            continue;
+        }
+        let label = format!(
+            "Go build <b>{}</>",
+            path.file_name().unwrap().to_string_lossy()
+        );
+        remove(&path, &label, dir_size(&path))?;
+    }
+    // Left behind by interrupted installs, recent ones may still be in progress
+    if let Ok(entries) = get_staging_dir()?.read_dir() {
+        for entry in entries {
+            let path = entry?.path();
+            let modified = path.metadata().and_then(|metadata| metadata.modified())?;
+            if modified > cutoff {
+                continue;
+            }
+            let label = format!(
+                "staged install <b>{}</>",
+                path.file_name().unwrap().to_string_lossy()
+            );
+            remove(&path, &label, dir_size(&path))?;
+        }
+    }
+
+    match (dry_run, freed) {
+        (_, 0) => info!("Nothing to prune"),
+        (true, _) => info!("Pruning would free <cyan>{}</>", format_size(freed)),
+        (false, _) => success!("Freed <cyan>{}</>", format_size(freed)),
+    }
+    Ok(())
+}
+
+fn verify(remove: bool) -> Result<()> {
+    let mut broken = 0;
+    for filter in cached_filters()? {
+        let problems = filter.verify()?;
+        if problems.is_empty() {
+            continue;
+        }
+        broken += 1;
+        warn!(
+            "Filter <b>{}/{}@{}</> is broken",
+            filter.url, filter.name, filter.version
+        );
+        for problem in problems {
+            log!("<yellow> >></> {problem}");
+        }
+        if remove {
+            rimraf(&filter.path)?;
+            remove_empty_parents(&filter.path)?;
+        }
+    }
+    match (broken, remove) {
+        (0, _) => success!("All cached filters are valid"),
+        (_, true) => success!("Removed {broken} broken filter(s)"),
+        (_, false) => {
+            bail!("Found {broken} broken filter(s), run `rgl cache verify --remove` to remove them")
+        }
+    }
+    Ok(())
+}
+
+/// Remove directories left empty after removing `path`, up to the cache directory.
+fn remove_empty_parents(path: &Path) -> Result<()> {
+    let cache_dir = get_cache_dir()?;
+    let mut parent = path.parent();
+    while let Some(dir) = parent {
+        if dir == cache_dir || !dir.starts_with(&cache_dir) || fs::remove_dir(dir).is_err() {
+            break;
+        }
+        parent = dir.parent();
+    }
+    Ok(())
+}
+
+/// Parses durations like `30d`, `12h`, `2w` or `90m`.
+fn parse_duration(value: &str) -> Result<Duration> {
+    let unit = value.trim_start_matches(|c: char| c.is_ascii_digit());
+    let amount: u64 = value[..value.len() - unit.len()]
+        .parse()
+        .map_err(|_| anyhow!("Expected a number followed by a unit, e.g. `30d`"))?;
+    let secs = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => bail!("Unknown unit `{unit}`, expected one of `m`, `h`, `d` or `w`"),
+    };
+    let secs = amount
+        .checked_mul(secs)
+        .context("Invalid duration, the value is too large")?;
+    Ok(Duration::from_secs(secs))
+}
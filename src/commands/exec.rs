@@ -1,7 +1,7 @@
 use super::Command;
 use crate::fs::{copy_dir, empty_dir, sync_dir};
 use crate::info;
-use crate::rgl::{Config, Filter, FilterContext, GlobalFilters, Session, Temp};
+use crate::rgl::{Config, Filter, FilterContext, FilterDefinition, GlobalFilters, Session, Temp};
 use anyhow::Result;
 use clap::Args;
 
@@ -33,15 +33,18 @@ impl Command for Exec {
         }
         copy_dir(&data, &temp.data)?;
 
-        if let Ok(filter) = config.get_filter(&self.filter) {
+        if let Ok(mut filter) = config.get_filter(&self.filter) {
+            if let FilterDefinition::Remote(remote) = &mut filter {
+                remote.resolve_range(&self.filter)?;
+            }
             info!("Running filter <filter>{}</>", self.filter);
-            let context = FilterContext::new(&self.filter, &filter)?;
+            let context = self.context(&filter)?;
             filter.run(&context, &temp.root, &self.run_args)?;
         } else {
             let global_filters = GlobalFilters::load()?;
             let filter = global_filters.get(&self.filter)?.into();
             info!("Running global filter <filter>{}</>", self.filter);
-            let context = FilterContext::new(&self.filter, &filter)?;
+            let context = self.context(&filter)?;
             filter.run(&context, &temp.root, &self.run_args)?;
         }
 
@@ -63,3 +66,18 @@ impl Command for Exec {
         format!("Error executing filter <filter>{}</>", self.filter)
     }
 }
+
+impl Exec {
+    /// Checks the filter before running it, settings are passed as the first argument, same as
+    /// in profiles.
+    fn context(&self, filter: &FilterDefinition) -> Result<FilterContext> {
+        let mut context = FilterContext::new(&self.filter, filter)?;
+        context.settings = self
+            .run_args
+            .first()
+            .and_then(|arg| serde_json::from_str(arg).ok());
+        context.check_requirements()?;
+        context.validate_settings()?;
+        Ok(context)
+    }
+}
@@ -0,0 +1,132 @@
+use super::Command;
+use crate::log;
+use crate::rgl::{
+    get_repo_cache_dir, git_cache_path, git_clone_url, git_fetch, git_read_file, Config,
+    FilterDefinition, GlobalFilters, RemoteFilterConfig, Resolver,
+};
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use std::path::Path;
+
+/// Inspect filters available in the resolvers
+#[derive(Args)]
+pub struct Filter {
+    #[command(subcommand)]
+    command: FilterCommand,
+}
+
+#[derive(Subcommand)]
+enum FilterCommand {
+    /// Show the versions, runtimes and README of a filter
+    Info {
+        /// Filter name, or `<git-url>/<filter-name>` for filters that are not in a resolver
+        filter: String,
+    },
+}
+
+impl Command for Filter {
+    fn dispatch(&self) -> Result<()> {
+        match &self.command {
+            FilterCommand::Info { filter } => info(filter),
+        }
+    }
+    fn error_context(&self) -> String {
+        match &self.command {
+            FilterCommand::Info { filter } => format!("Error getting info of filter {filter}"),
+        }
+    }
+}
+
+fn info(arg: &str) -> Result<()> {
+    let (name, url) = match arg.rsplit_once('/') {
+        Some((url, name)) => (name.to_owned(), url.to_owned()),
+        None => (arg.to_owned(), Resolver::resolve_url(arg)?),
+    };
+    let versions = Resolver::versions(&name, &url)?;
+
+    let clone_url = git_clone_url(&url);
+    let repo = git_fetch(
+        &clone_url,
+        &get_repo_cache_dir()?.join(git_cache_path(&url)),
+    )?;
+    let rev = match versions.last() {
+        Some(version) => format!("refs/tags/{name}-{version}"),
+        None => "refs/remotes/origin/HEAD".to_owned(),
+    };
+    let config: RemoteFilterConfig =
+        serde_json::from_slice(&git_read_file(&repo, &rev, &format!("{name}/filter.json"))?)
+            .with_context(|| format!("Failed to parse filter.json of <filter>{name}</>"))?;
+    let readme = ["README.md", "readme.md", "README"]
+        .iter()
+        .find_map(|file| git_read_file(&repo, &rev, &format!("{name}/{file}")).ok());
+
+    log!("<filter>{name}</>");
+    if let Some(description) = Resolver::description(&name) {
+        log!("  {description}");
+    }
+    log!("<green>URL:</> {url}");
+    let versions: Vec<_> = versions.iter().map(|v| v.to_string()).collect();
+    match versions.is_empty() {
+        true => log!("<green>Versions:</> <bright-black>no releases, using HEAD</>"),
+        false => log!("<green>Versions:</> <cyan>{}</>", versions.join(", ")),
+    }
+    log!("<green>Installed:</> {}", installed(&name)?);
+
+    log!("<green>Filters:</>");
+    for entry in &config.filters {
+        match &entry.filter {
+            FilterDefinition::Local(filter) => log!("  {filter}"),
+            FilterDefinition::Remote(remote) => {
+                let dep_name = entry.name.as_deref().unwrap_or_default();
+                log!(
+                    "  {dep_name} <bright-black>{}@{}</>",
+                    remote.url,
+                    remote.version
+                );
+            }
+            FilterDefinition::Path(filter) => log!("  <bright-black>{}</>", filter.path),
+        }
+    }
+    let requirements = &config.requirements;
+    let mut requires: Vec<_> = requirements
+        .runtimes
+        .iter()
+        .map(|(runtime, req)| format!("{runtime} {req}"))
+        .collect();
+    if let Some(req) = &requirements.rgl_version {
+        requires.push(format!("rgl {req}"));
+    }
+    if let Some(os) = &requirements.os {
+        requires.push(os.join("/"));
+    }
+    if let Some(arch) = &requirements.arch {
+        requires.push(arch.join("/"));
+    }
+    if !requires.is_empty() {
+        log!("<green>Requires:</> {}", requires.join(", "));
+    }
+
+    if let Some(readme) = readme {
+        log!("<green>README:</>");
+        // Printed as is, Markdown could contain tags the logger would try to style
+        println!("{}", String::from_utf8_lossy(&readme).trim_end());
+    }
+    Ok(())
+}
+
+/// Where the filter is installed, e.g. `project 1.0.0, global 1.1.0`.
+fn installed(name: &str) -> Result<String> {
+    let mut installed = vec![];
+    if Path::new("./config.json").is_file() {
+        if let Ok(FilterDefinition::Remote(remote)) = Config::load()?.get_filter(name) {
+            installed.push(format!("project <cyan>{}</>", remote.version));
+        }
+    }
+    if let Ok(remote) = GlobalFilters::load()?.get(name) {
+        installed.push(format!("global <cyan>{}</>", remote.version));
+    }
+    match installed.is_empty() {
+        true => Ok("<bright-black>no</>".to_owned()),
+        false => Ok(installed.join(", ")),
+    }
+}
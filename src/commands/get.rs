@@ -1,27 +1,32 @@
 use super::Command;
 use crate::info;
-use crate::rgl::{Config, Filter, FilterContext, FilterDefinition, Session};
-use anyhow::Result;
+use crate::rgl::{
+    install_filters, Config, Filter, FilterContext, FilterDefinition, Lockfile, Session,
+};
+use anyhow::{Context, Result};
 use clap::Args;
+use std::num::NonZeroUsize;
 
 /// Fetch filters defined in the `config.json` file
 #[derive(Args)]
 pub struct Get {
     #[arg(short, long)]
     force: bool,
+    /// Number of filters to install at once
+    #[arg(short, long, default_value = "4")]
+    jobs: NonZeroUsize,
 }
 
 impl Command for Get {
     fn dispatch(&self) -> Result<()> {
         let config = Config::load()?;
+        let mut lockfile = Lockfile::load()?;
         let mut session = Session::lock()?;
         let data_path = config.get_data_path();
+        let mut remote_filters = vec![];
         for (name, filter) in config.get_filters()? {
             match filter {
-                FilterDefinition::Remote(remote) => {
-                    info!("Downloading filter <filter>{name}</>...");
-                    remote.install(&name, Some(&data_path), false)?;
-                }
+                FilterDefinition::Remote(remote) => remote_filters.push((name, remote)),
                 filter => {
                     info!("Installing dependencies for <filter>{name}</>...");
                     let context = FilterContext::new(&name, &filter)?;
@@ -29,6 +34,22 @@ impl Command for Get {
                 }
             };
         }
+        let installed = install_filters(self.jobs.get(), remote_filters, |name, remote| {
+            info!("Downloading filter <filter>{name}</>...");
+            remote.resolve_range(name)?;
+            match lockfile.get(name) {
+                Some(locked) if locked.matches(remote) => {
+                    remote.install_locked(name, Some(&data_path), false, locked)
+                }
+                _ => remote.install(name, Some(&data_path), false),
+            }
+            .with_context(|| format!("Failed to install filter <filter>{name}</>"))
+        })?;
+        for (name, _, locked) in installed {
+            lockfile.insert(&name, locked);
+        }
+        lockfile.retain(&config)?;
+        lockfile.save()?;
         info!("Success getting filters!");
         session.unlock()
     }
@@ -1,6 +1,6 @@
 use super::Command;
 use crate::log;
-use crate::rgl::{Config, FilterDefinition, GlobalFilters};
+use crate::rgl::{Config, FilterDefinition, GlobalFilters, RemoteFilter};
 use anyhow::Result;
 use clap::Args;
 
@@ -29,18 +29,31 @@ fn list_project() -> Result<()> {
 
     let mut local_filters = vec![];
     let mut remote_filters = vec![];
+    let mut path_filters = vec![];
     for (name, filter) in config.get_filters()? {
         match filter {
             FilterDefinition::Local(filter) => {
                 local_filters.push((name, filter.to_string()));
             }
             FilterDefinition::Remote(filter) => {
-                remote_filters.push((name, filter.version));
+                let version = match &filter.range {
+                    Some(_) if filter.is_unresolved() => {
+                        format!("{} (not installed)", filter.version)
+                    }
+                    Some(range) => format!("{} ({range})", filter.version),
+                    None => filter.version.to_owned(),
+                };
+                remote_filters.push((name.to_owned(), version));
+                push_dependencies(&mut remote_filters, &name, &filter, &mut vec![])?;
+            }
+            FilterDefinition::Path(filter) => {
+                path_filters.push((name, filter.path));
             }
         }
     }
     print("Local filters:", &local_filters);
     print("Remote filters:", &remote_filters);
+    print("Path filters:", &path_filters);
     Ok(())
 }
 
@@ -50,11 +63,32 @@ fn list_global() -> Result<()> {
     let mut filters = vec![];
     for (name, filter) in global_filters.iter() {
         filters.push((name.to_owned(), filter.version.to_owned()));
+        push_dependencies(&mut filters, name, filter, &mut vec![])?;
     }
     print("Global filters:", &filters);
     Ok(())
 }
 
+/// Adds the installed dependencies of a remote filter as an indented tree.
+fn push_dependencies(
+    filters: &mut Vec<(String, String)>,
+    name: &str,
+    filter: &RemoteFilter,
+    stack: &mut Vec<String>,
+) -> Result<()> {
+    stack.push(name.to_owned());
+    for (dep_name, dep) in filter.dependencies(name)? {
+        let indent = "  ".repeat(stack.len() - 1);
+        filters.push((format!("{indent}└ {dep_name}"), dep.version.to_owned()));
+        // Cycles are reported when installing, just avoid looping forever here
+        if !stack.contains(&dep_name) {
+            push_dependencies(filters, &dep_name, &dep, stack)?;
+        }
+    }
+    stack.pop();
+    Ok(())
+}
+
 fn print(label: &str, filters: &Vec<(String, String)>) {
     if filters.is_empty() {
         return;
@@ -1,33 +1,43 @@
 mod add;
 mod apply;
+mod cache;
 mod clean;
 mod exec;
+mod filter;
 mod get;
 mod info;
 mod init;
 mod install;
 mod list;
+mod outdated;
 mod remove;
 mod run;
+mod search;
 mod uninstall;
 mod update;
 mod upgrade;
+mod validate;
 mod watch;
 
 pub use self::add::*;
 pub use self::apply::*;
+pub use self::cache::*;
 pub use self::clean::*;
 pub use self::exec::*;
+pub use self::filter::*;
 pub use self::get::*;
 pub use self::info::*;
 pub use self::init::*;
 pub use self::install::*;
 pub use self::list::*;
+pub use self::outdated::*;
 pub use self::remove::*;
 pub use self::run::*;
+pub use self::search::*;
 pub use self::uninstall::*;
 pub use self::update::*;
 pub use self::upgrade::*;
+pub use self::validate::*;
 pub use self::watch::*;
 
 use anyhow::Result;
@@ -0,0 +1,187 @@
+use super::Command;
+use crate::rgl::{
+    parse_version_range, Config, FilterDefinition, GlobalFilters, RemoteFilter, Resolver,
+};
+use crate::{log, success, warn};
+use anyhow::{bail, Result};
+use clap::Args;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::path::Path;
+
+/// List remote filters that have newer versions available
+#[derive(Args)]
+pub struct Outdated {
+    /// Print the report as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Report {
+    name: String,
+    /// `project` or `global`
+    scope: &'static str,
+    url: String,
+    current: String,
+    /// Newest version allowed by the version range, or the current major version
+    wanted: String,
+    latest: String,
+    outdated: bool,
+    /// Why the versions of the filter couldn't be checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl Command for Outdated {
+    fn dispatch(&self) -> Result<()> {
+        let mut filters = vec![];
+        if Path::new("./config.json").is_file() {
+            for (name, filter) in Config::load()?.get_filters()? {
+                if let FilterDefinition::Remote(remote) = filter {
+                    filters.push(("project", name, remote));
+                }
+            }
+        }
+        for (name, remote) in GlobalFilters::load()?.iter() {
+            filters.push(("global", name.to_owned(), remote.to_owned()));
+        }
+
+        let reports: Vec<_> = filters
+            .par_iter()
+            .map(|(scope, name, remote)| report(scope, name, remote))
+            .collect();
+        let outdated = reports.iter().filter(|report| report.outdated).count();
+        let failed = reports
+            .iter()
+            .filter(|report| report.error.is_some())
+            .count();
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&reports)?);
+            // Keep stdout parseable, the exit code alone tells CI that filters are outdated or
+            // couldn't be checked
+            if outdated > 0 || failed > 0 {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        print(
+            "Project filters:",
+            reports.iter().filter(|r| r.scope == "project"),
+        );
+        print(
+            "Global filters:",
+            reports.iter().filter(|r| r.scope == "global"),
+        );
+        for report in reports.iter() {
+            if let Some(error) = &report.error {
+                warn!(
+                    "Failed to check filter <filter>{}</> for updates\n<yellow> >></> {error}",
+                    report.name
+                );
+            }
+        }
+        if failed > 0 {
+            bail!("{failed} filter(s) couldn't be checked for updates");
+        }
+        if outdated > 0 {
+            bail!(
+                "{outdated} filter(s) are outdated, run `rgl update` to update them, or \
+                 `rgl update --major` for new major versions"
+            );
+        }
+        success!("All filters are up-to-date");
+        Ok(())
+    }
+    fn error_context(&self) -> String {
+        "Error checking for outdated filters".to_owned()
+    }
+}
+
+/// Filters pinned to a commit or without released versions are reported as `n/a` and never
+/// outdated. Filters whose versions can't be listed, e.g. because the repository can't be reached,
+/// are reported with an error.
+fn report(scope: &'static str, name: &str, remote: &RemoteFilter) -> Report {
+    let mut report = Report {
+        name: name.to_owned(),
+        scope,
+        url: remote.url.to_owned(),
+        current: remote.version.to_owned(),
+        wanted: NOT_AVAILABLE.to_owned(),
+        latest: NOT_AVAILABLE.to_owned(),
+        outdated: false,
+        error: None,
+    };
+    let versions = match Resolver::versions(name, &remote.url) {
+        Ok(versions) => versions,
+        Err(e) => {
+            let chain: Vec<_> = e
+                .chain()
+                .filter_map(|e| e.to_string().lines().next().map(str::to_owned))
+                .collect();
+            report.error = Some(chain.join(": "));
+            report.wanted = UNKNOWN.to_owned();
+            report.latest = UNKNOWN.to_owned();
+            return report;
+        }
+    };
+    let constraint = parse_version_range(&remote.update_constraint());
+    let wanted = versions.iter().rfind(|version| {
+        constraint
+            .as_ref()
+            .is_none_or(|range| range.matches(version))
+    });
+    if let Some(wanted) = wanted {
+        report.wanted = wanted.to_string();
+    }
+    if let Some(latest) = versions.last() {
+        report.latest = latest.to_string();
+        // Unlocked ranges are installed at the newest matching version by `rgl get`
+        report.outdated = !remote.is_unresolved() && report.latest != remote.version;
+    }
+    report
+}
+
+const NOT_AVAILABLE: &str = "n/a";
+const UNKNOWN: &str = "?";
+
+fn print<'a>(label: &str, reports: impl Iterator<Item = &'a Report>) {
+    let reports: Vec<_> = reports.collect();
+    if reports.is_empty() {
+        return;
+    }
+    let width = |f: fn(&Report) -> &str, header: &str| {
+        reports
+            .iter()
+            .map(|r| f(r).len())
+            .max()
+            .unwrap_or_default()
+            .max(header.len())
+    };
+    let name_width = width(|r| &r.name, "Name");
+    let current_width = width(|r| &r.current, "Current");
+    let wanted_width = width(|r| &r.wanted, "Wanted");
+    log!("<green>{label}</>");
+    log!(
+        "  <bright-black>{:name_width$}  {:current_width$}  {:wanted_width$}  Latest</>",
+        "Name",
+        "Current",
+        "Wanted"
+    );
+    for r in reports {
+        let color = match (r.outdated, &r.error) {
+            (_, Some(_)) => "red",
+            (true, _) => "yellow",
+            (false, _) => "cyan",
+        };
+        log!(
+            "  {:name_width$}  <{color}>{:current_width$}</>  <cyan>{:wanted_width$}</>  <cyan>{}</>",
+            r.name,
+            r.current,
+            r.wanted,
+            r.latest
+        );
+    }
+}
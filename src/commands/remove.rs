@@ -1,5 +1,5 @@
 use super::Command;
-use crate::rgl::{Config, ConfigCst, Session};
+use crate::rgl::{Config, ConfigCst, Lockfile, Session};
 use crate::{info, warn};
 use anyhow::Result;
 use clap::Args;
@@ -17,8 +17,10 @@ impl Command for Remove {
         // Make sure it's a valid config
         let _ = Config::load()?;
         let config_cst = ConfigCst::load()?;
+        let mut lockfile = Lockfile::load()?;
         let mut session = Session::lock()?;
         for name in &self.filters {
+            lockfile.remove(name);
             if config_cst.remove_filter(name) {
                 info!("Removed filter <filter>{name}</>");
             } else {
@@ -26,6 +28,7 @@ impl Command for Remove {
             }
         }
         config_cst.save()?;
+        lockfile.save()?;
         session.unlock()
     }
     fn error_context(&self) -> String {
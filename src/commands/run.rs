@@ -1,5 +1,5 @@
 use super::Command;
-use crate::rgl::{runner, Config, Session, UserConfig};
+use crate::rgl::{runner, Config, Lockfile, Session, UserConfig};
 use anyhow::Result;
 use clap::Args;
 
@@ -14,11 +14,17 @@ pub struct Run {
     /// Enable this if filters are not working correctly
     #[arg(long)]
     compat: bool,
+    /// Fail if rgl.lock doesn't match the config
+    #[arg(long)]
+    frozen: bool,
 }
 
 impl Command for Run {
     fn dispatch(&self) -> Result<()> {
         let config = Config::load()?;
+        if self.frozen {
+            Lockfile::load()?.verify(&config)?;
+        }
         let mut session = Session::lock()?;
 
         smol::block_on(runner(
@@ -0,0 +1,46 @@
+use super::Command;
+use crate::rgl::{is_commit, Resolver};
+use crate::{log, warn};
+use anyhow::Result;
+use clap::Args;
+use rayon::prelude::*;
+
+/// Search filters available in the resolvers
+#[derive(Args)]
+pub struct Search {
+    /// Text to look for in filter names and descriptions
+    term: String,
+}
+
+impl Command for Search {
+    fn dispatch(&self) -> Result<()> {
+        let results = Resolver::search(&self.term)?;
+        if results.is_empty() {
+            warn!("No filters found matching <b>{}</>", self.term);
+            return Ok(());
+        }
+        // Tags are listed once per repository, so this is mostly waiting on the network
+        let versions: Vec<_> = results
+            .par_iter()
+            .map(|(name, data)| {
+                match Resolver::resolve_version(name, &data.url, Some("latest".to_owned())) {
+                    // Filters without releases resolve to a commit
+                    Ok(version) if is_commit(&version) => version[..7].to_owned(),
+                    Ok(version) => version,
+                    Err(_) => "unknown".to_owned(),
+                }
+            })
+            .collect();
+        for ((name, data), version) in results.iter().zip(versions) {
+            log!("<filter>{name}</> <cyan>{version}</>");
+            log!("  <bright-black>{}</>", data.url);
+            if let Some(description) = &data.description {
+                log!("  {description}");
+            }
+        }
+        Ok(())
+    }
+    fn error_context(&self) -> String {
+        "Error searching filters".to_owned()
+    }
+}
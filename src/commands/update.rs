@@ -1,8 +1,9 @@
 use super::Command;
-use crate::rgl::{Config, ConfigCst, FilterDefinition, Session};
+use crate::rgl::{install_filters, Config, ConfigCst, FilterDefinition, Lockfile, Session};
 use crate::{info, warn};
 use anyhow::{Context, Result};
 use clap::Args;
+use std::num::NonZeroUsize;
 
 /// Update filter(s) in the current project
 #[derive(Args)]
@@ -10,41 +11,53 @@ pub struct Update {
     filters: Vec<String>,
     #[arg(short, long)]
     force: bool,
+    /// Allow updating to a new major version, outside of the configured version range
+    #[arg(long)]
+    major: bool,
+    /// Number of filters to update at once
+    #[arg(short, long, default_value = "4")]
+    jobs: NonZeroUsize,
 }
 
 impl Command for Update {
     fn dispatch(&self) -> Result<()> {
         let config = Config::load()?;
         let config_cst = ConfigCst::load()?;
+        let mut lockfile = Lockfile::load()?;
         let mut session = Session::lock()?;
         let data_path = config.get_data_path();
 
         info!("Updating filters...");
+        let mut remote_filters = vec![];
         if self.filters.is_empty() {
             for (name, definition) in config.get_filters()? {
-                if let FilterDefinition::Remote(mut remote) = definition {
-                    remote
-                        .update(&name, Some(&data_path), self.force)
-                        .with_context(|| format!("Failed to update filter <filter>{name}</>"))?;
-                    config_cst.add_filter(&name, remote);
+                if let FilterDefinition::Remote(remote) = definition {
+                    remote_filters.push((name, remote));
                 }
             }
         } else {
             for name in &self.filters {
                 let definition = config.get_filter(name)?;
-                if let FilterDefinition::Remote(mut remote) = definition {
-                    remote
-                        .update(name, Some(&data_path), self.force)
-                        .with_context(|| format!("Failed to update filter <filter>{name}</>"))?;
-                    config_cst.add_filter(name, remote);
+                if let FilterDefinition::Remote(remote) = definition {
+                    remote_filters.push((name.to_owned(), remote));
                 } else {
                     warn!("Filter <filter>{name}</> is not a remote filter, skipping...");
                 }
             }
         }
+        let updated = install_filters(self.jobs.get(), remote_filters, |name, remote| {
+            remote
+                .update(name, Some(&data_path), self.force, self.major)
+                .with_context(|| format!("Failed to update filter <filter>{name}</>"))
+        })?;
+        for (name, remote, locked) in updated {
+            lockfile.insert(&name, locked);
+            config_cst.add_filter(&name, remote);
+        }
 
         info!("Filters successfully updated");
         config_cst.save()?;
+        lockfile.save()?;
         session.unlock()
     }
     fn error_context(&self) -> String {
@@ -1,5 +1,5 @@
 use super::Command;
-use crate::rgl::fetch_latest_version;
+use crate::rgl::{ensure_online, fetch_latest_version};
 use crate::{info, loading, success};
 use anyhow::{Context, Result};
 use clap::{crate_version, Args};
@@ -23,6 +23,7 @@ pub struct Upgrade {
 
 impl Command for Upgrade {
     fn dispatch(&self) -> Result<()> {
+        ensure_online("Upgrading rgl")?;
         info!("Looking up latest version");
         let current_version = crate_version!();
         let latest_version = fetch_latest_version()?;
@@ -0,0 +1,50 @@
+use super::Command;
+use crate::rgl::{Config, FilterContext, FilterRunner, ProfileEntry};
+use crate::{error, info};
+use anyhow::{bail, Result};
+use clap::Args;
+
+/// Validate filter settings in all profiles against their schemas
+#[derive(Args)]
+pub struct Validate {}
+
+impl Command for Validate {
+    fn dispatch(&self) -> Result<()> {
+        let config = Config::load()?;
+        let mut checked = 0;
+        let mut failed = 0;
+        for (profile_name, profile) in config.get_profiles() {
+            let runners = profile.filters.iter().flat_map(|entry| match entry {
+                ProfileEntry::Filter(runner) => std::slice::from_ref(runner),
+                ProfileEntry::AsyncFilter { async_filters } => async_filters.as_slice(),
+            });
+            for runner in runners {
+                let FilterRunner::Filter {
+                    filter_name,
+                    use_global,
+                    settings,
+                    ..
+                } = runner
+                else {
+                    continue;
+                };
+                let (filter_name, filter) = config.get_profile_filter(filter_name, *use_global)?;
+                let mut context = FilterContext::new(&filter_name, &filter)?;
+                context.settings = settings.to_owned();
+                checked += 1;
+                if let Err(e) = context.validate_settings() {
+                    failed += 1;
+                    error!("[{profile_name}] {e}");
+                }
+            }
+        }
+        if failed > 0 {
+            bail!("{failed} of {checked} filter(s) have invalid settings");
+        }
+        info!("Settings of {checked} filter(s) are valid");
+        Ok(())
+    }
+    fn error_context(&self) -> String {
+        "Error validating filter settings".to_owned()
+    }
+}
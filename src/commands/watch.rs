@@ -1,5 +1,5 @@
 use super::Command;
-use crate::rgl::{runner, Config, MinecraftServer, Session, UserConfig};
+use crate::rgl::{runner, Config, FilterDaemon, MinecraftServer, Session, UserConfig};
 use crate::{error, info, log, warn};
 use anyhow::Result;
 use clap::Args;
@@ -32,6 +32,7 @@ impl Command for Watch {
             None
         };
 
+        FilterDaemon::enable();
         smol::block_on(async {
             loop {
                 let config = Config::load()?;
@@ -69,6 +70,7 @@ impl Command for Watch {
                     watcher.wait_debounced(Duration::from_millis(100)).await;
                 }
 
+                FilterDaemon::set_changed_paths(watcher.take_changed_paths());
                 warn!("Changes detected, restarting...");
                 session.unlock()?;
             }
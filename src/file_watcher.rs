@@ -1,16 +1,23 @@
 use anyhow::{Context, Result};
 use notify::{recommended_watcher, Event, RecommendedWatcher, RecursiveMode, Watcher};
 use smol::{channel, Timer};
-use std::{path::Path, time::Duration};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 pub struct FileWatcher {
     rx: channel::Receiver<()>,
     watcher: RecommendedWatcher,
+    changed_paths: Arc<Mutex<Vec<PathBuf>>>,
 }
 
 impl FileWatcher {
     pub fn new() -> Result<Self> {
         let (tx, rx) = channel::unbounded();
+        let changed_paths = Arc::new(Mutex::new(vec![]));
+        let paths = changed_paths.clone();
         let watcher = recommended_watcher(move |event: notify::Result<Event>| {
             if let Ok(e) = event {
                 if e.kind.is_access() || e.kind.is_other() {
@@ -20,12 +27,19 @@ impl FileWatcher {
                 if e.kind.is_modify() && e.paths.iter().all(|p| p.is_dir()) {
                     return;
                 }
+                // Collecting the components drops the `./` from watched relative paths
+                let changed = e.paths.iter().map(|p| p.components().collect::<PathBuf>());
+                paths.lock().unwrap().extend(changed);
                 let _ = tx.send_blocking(());
             }
         })
         .context("Failed to create file watcher")?;
 
-        Ok(Self { rx, watcher })
+        Ok(Self {
+            rx,
+            watcher,
+            changed_paths,
+        })
     }
 
     pub fn add_path(&mut self, path: impl AsRef<Path>) -> Result<()> {
@@ -67,5 +81,14 @@ impl FileWatcher {
 
     pub fn flush(&self) {
         while self.rx.try_recv().is_ok() {}
+        self.changed_paths.lock().unwrap().clear();
+    }
+
+    /// Returns the paths changed since the last flush.
+    pub fn take_changed_paths(&self) -> Vec<PathBuf> {
+        let mut paths = std::mem::take(&mut *self.changed_paths.lock().unwrap());
+        paths.sort();
+        paths.dedup();
+        paths
     }
 }
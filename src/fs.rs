@@ -3,12 +3,14 @@ use dashmap::DashMap;
 use dunce::canonicalize;
 use jsonc_parser::ParseOptions;
 use rayon::prelude::*;
+use sha2::{Digest, Sha256};
 use std::{
     fs, io,
     path::{Path, PathBuf},
     sync::LazyLock,
     time::SystemTime,
 };
+use walkdir::{DirEntry, WalkDir};
 
 fn copy_dir_impl(from: &Path, to: &Path) -> Result<()> {
     fs::create_dir_all(to)?;
@@ -90,6 +92,8 @@ pub fn rimraf(path: impl AsRef<Path>) -> Result<()> {
             match e.kind() {
                 io::ErrorKind::PermissionDenied => {
                     let mut perm = metadata.permissions();
+                    // The entry is removed right after, so it doesn't matter who can write it
+                    #[allow(clippy::permissions_set_readonly_false)]
                     perm.set_readonly(false);
                     fs::set_permissions(path, perm)?;
                     rm(&path)?;
@@ -142,6 +146,70 @@ pub fn rimraf(path: impl AsRef<Path>) -> Result<()> {
     }
 }
 
+/// Hash the contents of a directory. Hidden entries and installed dependencies (`node_modules`,
+/// `.venv`, `__pycache__`) are skipped.
+pub fn hash_dir(path: impl AsRef<Path>) -> Result<String> {
+    let path = path.as_ref();
+    hash_files(path, &list_files(path))
+}
+
+/// Files hashed by [`hash_dir`], relative to `path` and with `/` separators on every platform.
+pub fn list_files(path: impl AsRef<Path>) -> Vec<String> {
+    let path = path.as_ref();
+    let mut files: Vec<String> = WalkDir::new(path)
+        .into_iter()
+        .filter_entry(|entry| !is_ignored_entry(entry))
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| {
+            let relative = entry.path().strip_prefix(path).unwrap_or(entry.path());
+            relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Hidden entries and installed dependencies, which aren't part of a filter's own files. The root
+/// of the walk is never ignored.
+pub fn is_ignored_entry(entry: &DirEntry) -> bool {
+    let name = entry.file_name().to_string_lossy();
+    entry.depth() > 0 && (name.starts_with('.') || name == "node_modules" || name == "__pycache__")
+}
+
+/// Hash the given files in a directory, see [`list_files`]. Missing files are hashed as empty,
+/// so removing a file still changes the hash.
+pub fn hash_files(path: impl AsRef<Path>, files: &[String]) -> Result<String> {
+    let path = path.as_ref();
+    let mut hasher = Sha256::new();
+    for file in files {
+        hasher.update(file.as_bytes());
+        hasher.update([0]);
+        match fs::read(path.join(file)) {
+            Ok(content) => hasher.update(content),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        hasher.update([0]);
+    }
+    Ok(format!("sha256-{:x}", hasher.finalize()))
+}
+
+/// Total size of the files in a directory, in bytes.
+pub fn dir_size(path: impl AsRef<Path>) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
 /// Checks if directory exists and is not empty
 pub fn is_dir_empty(path: &Path) -> Result<bool> {
     Ok(!path.is_dir() || path.read_dir()?.next().is_none())
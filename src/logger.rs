@@ -62,6 +62,15 @@ impl Logger {
     }
 }
 
+/// Details listed under an error message, one `>>` line each.
+pub fn format_problems(problems: &[String]) -> String {
+    problems
+        .iter()
+        .map(|problem| format!("<yellow> >></> {problem}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 #[macro_export]
 macro_rules! log {
     ($($arg:tt)*) => {
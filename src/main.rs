@@ -5,7 +5,7 @@ mod logger;
 mod rgl;
 
 use anyhow::{Context, Result};
-use clap::{crate_name, Parser, Subcommand};
+use clap::{builder::FalseyValueParser, crate_name, Parser, Subcommand};
 use commands::*;
 use enum_dispatch::enum_dispatch;
 use logger::Logger;
@@ -14,6 +14,7 @@ use std::thread;
 fn main() {
     let cli = Cli::parse();
     Logger::set_debug(cli.debug);
+    rgl::set_offline(cli.offline);
     if let Err(e) = run_command(cli) {
         error!("{e}");
         e.chain().skip(1).for_each(|e| log!("<red>[+]</> {e}"));
@@ -29,6 +30,7 @@ fn run_command(cli: Cli) -> Result<()> {
     let handle = match cli.subcommand {
         // Don't trigger update check when running these commands
         Subcommands::Upgrade(_) | Subcommands::Watch(_) => None,
+        _ if cli.offline => None,
         _ => Some(thread::spawn(rgl::version_check)),
     };
     measure_time!("Total time", {
@@ -61,6 +63,9 @@ struct Cli {
     /// Print debug messages
     #[arg(long, global = true)]
     debug: bool,
+    /// Only use cached resolver data and installed filters
+    #[arg(long, global = true, env = "RGL_OFFLINE", value_parser = FalseyValueParser::new())]
+    offline: bool,
 }
 
 #[derive(Subcommand)]
@@ -68,17 +73,22 @@ struct Cli {
 enum Subcommands {
     Add(Add),
     Apply(Apply),
+    Cache(Cache),
     Clean(Clean),
     Exec(Exec),
+    Filter(Filter),
     Get(Get),
     Info(Info),
     Init(Init),
     Install(Install),
     List(List),
+    Outdated(Outdated),
     Remove(Remove),
     Run(Run),
+    Search(Search),
     Uninstall(Uninstall),
     Update(Update),
     Upgrade(Upgrade),
+    Validate(Validate),
     Watch(Watch),
 }
@@ -0,0 +1,194 @@
+use super::{auth_token, is_commit, url_host};
+use crate::debug;
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use sha2::{Digest, Sha256};
+use std::{
+    fs, io,
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+use tar::EntryType;
+use zip::ZipArchive;
+
+/// Download an archive. Returns `None` if it doesn't exist or can't be downloaded, so the caller
+/// can fall back to git.
+pub fn download_archive(url: &str) -> Option<Vec<u8>> {
+    debug!("Downloading archive: {url}");
+    let mut request = ureq::get(url).timeout(Duration::from_secs(60));
+    if let Some(token) = url_host(url).and_then(|host| auth_token(&host)) {
+        request = request.set("Authorization", &format!("Bearer {token}"));
+    }
+    let response = match request.call() {
+        Ok(response) => response,
+        Err(e) => {
+            debug!("Failed to download archive: {e}");
+            return None;
+        }
+    };
+    let mut bytes = vec![];
+    if let Err(e) = response.into_reader().read_to_end(&mut bytes) {
+        debug!("Failed to download archive: {e}");
+        return None;
+    }
+    Some(bytes)
+}
+
+/// Verify the archive against a checksum, either `sha256:<hex>` or just the hex digest.
+pub fn verify_checksum(bytes: &[u8], checksum: &str) -> Result<()> {
+    let expected = checksum.strip_prefix("sha256:").unwrap_or(checksum);
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    if !expected.eq_ignore_ascii_case(&actual) {
+        bail!(
+            "Archive checksum mismatch\n\
+             <yellow> >></> Expected: {expected}\n\
+             <yellow> >></> Found: {actual}"
+        );
+    }
+    Ok(())
+}
+
+/// Extract the `name` folder from a `.zip` or `.tar.gz` archive into `target`. The folder may be
+/// at the root of the archive, or inside a single top level directory like GitHub's
+/// `<repo>-<tag>/`. Returns the commit ID stored by `git archive`, if any.
+pub fn extract_filter(
+    bytes: &[u8],
+    is_zip: bool,
+    name: &str,
+    target: &Path,
+) -> Result<Option<String>> {
+    let mut commit = None;
+    let mut found = false;
+    if is_zip {
+        let mut archive = ZipArchive::new(io::Cursor::new(bytes))?;
+        if let Some(comment) = std::str::from_utf8(archive.comment())
+            .ok()
+            .filter(|comment| is_commit(comment))
+        {
+            commit = Some(comment.to_owned());
+        }
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)?;
+            let Some(path) = file
+                .enclosed_name()
+                .and_then(|path| filter_path(path, name))
+            else {
+                continue;
+            };
+            found = true;
+            let path = target.join(path);
+            if file.is_dir() {
+                fs::create_dir_all(&path)?;
+            } else {
+                create_parent(&path)?;
+                io::copy(&mut file, &mut fs::File::create(&path)?)?;
+                #[cfg(unix)]
+                if let Some(mode) = file.unix_mode() {
+                    use std::os::unix::fs::PermissionsExt;
+                    fs::set_permissions(&path, fs::Permissions::from_mode(mode & 0o777))?;
+                }
+            }
+        }
+    } else {
+        let mut archive = tar::Archive::new(GzDecoder::new(bytes));
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            if entry.header().entry_type() == EntryType::XGlobalHeader {
+                for extension in entry.pax_extensions()?.into_iter().flatten() {
+                    let extension = extension?;
+                    if extension.key() == Ok("comment") {
+                        commit = extension
+                            .value()
+                            .ok()
+                            .filter(|value| is_commit(value))
+                            .map(str::to_owned);
+                    }
+                }
+                continue;
+            }
+            // Links could point outside of the target directory
+            if !matches!(
+                entry.header().entry_type(),
+                EntryType::Regular | EntryType::Directory | EntryType::Continuous
+            ) {
+                continue;
+            }
+            let path = entry.path()?.into_owned();
+            let Some(path) = filter_path(&path, name) else {
+                continue;
+            };
+            found = true;
+            let path = target.join(path);
+            create_parent(&path)?;
+            entry.unpack(&path)?;
+        }
+    }
+    if !found {
+        bail!("Filter folder `{name}` not found in the archive");
+    }
+    Ok(commit)
+}
+
+/// Path relative to the filter folder, or `None` if the entry is outside of it.
+fn filter_path(path: &Path, name: &str) -> Option<PathBuf> {
+    let components: Vec<_> = path.components().collect();
+    // Reject anything that could escape the target directory
+    if !components.iter().all(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    let index = components
+        .iter()
+        .take(2)
+        .position(|c| c.as_os_str() == name)?;
+    Some(components[index + 1..].iter().collect())
+}
+
+fn create_parent(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(entry: &str) -> Option<PathBuf> {
+        filter_path(Path::new(entry), "name")
+    }
+
+    #[test]
+    fn entry_in_filter() {
+        assert_eq!(path("name/main.js"), Some(PathBuf::from("main.js")));
+        assert_eq!(path("name/data/a.json"), Some(PathBuf::from("data/a.json")));
+        assert_eq!(path("name"), Some(PathBuf::new()));
+    }
+
+    #[test]
+    fn entry_in_archive_prefix() {
+        assert_eq!(
+            path("repo-1.0.0/name/main.js"),
+            Some(PathBuf::from("main.js"))
+        );
+        assert_eq!(path("repo-1.0.0/other/main.js"), None);
+        assert_eq!(path("a/b/name/main.js"), None);
+    }
+
+    #[test]
+    fn absolute_entry() {
+        assert_eq!(path("/name/main.js"), None);
+        assert_eq!(path("/etc/passwd"), None);
+        if cfg!(windows) {
+            assert_eq!(path("C:\\name\\main.js"), None);
+        }
+    }
+
+    #[test]
+    fn parent_entry() {
+        assert_eq!(path("name/../../main.js"), None);
+        assert_eq!(path("../name/main.js"), None);
+        assert_eq!(path("./name/main.js"), None);
+    }
+}
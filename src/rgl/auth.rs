@@ -0,0 +1,35 @@
+use super::UserConfig;
+use std::env;
+use url::Url;
+
+/// Access token for a host, used for HTTPS git remotes and downloads. Read from the
+/// `RGL_TOKEN_<HOST>` environment variable, e.g. `RGL_TOKEN_GITHUB_COM`, or from `auth_tokens` in
+/// the user config. Tokens are only sent with requests, never stored with the filters.
+pub fn auth_token(host: &str) -> Option<String> {
+    env::var(auth_token_var(host))
+        .ok()
+        .filter(|token| !token.is_empty())
+        .or_else(|| UserConfig::auth_token(host))
+}
+
+/// Name of the environment variable holding the token for a host.
+pub fn auth_token_var(host: &str) -> String {
+    let host: String = host
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_uppercase(),
+            false => '_',
+        })
+        .collect();
+    format!("RGL_TOKEN_{host}")
+}
+
+/// Host of an URL, including scp-like `git@host:path` URLs.
+pub fn url_host(url: &str) -> Option<String> {
+    if let Ok(url) = Url::parse(url) {
+        return url.host_str().map(str::to_owned);
+    }
+    let rest = url.split_once('@').map_or(url, |(_, rest)| rest);
+    let host = rest.split([':', '/']).next()?;
+    (!host.is_empty()).then(|| host.to_owned())
+}
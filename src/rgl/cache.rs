@@ -0,0 +1,291 @@
+use super::{
+    get_cache_dir, get_current_dir, get_filter_cache_dir, get_go_cache_dir, get_repo_cache_dir,
+    git_cache_path, is_commit, Config, FilterDefinition, GlobalFilters, InstallInfo, RemoteFilter,
+    ResolverSource, UserConfig, INSTALL_INFO,
+};
+use crate::debug;
+use crate::fs::{dir_size, read_json, write_json};
+use anyhow::Result;
+use fslock::LockFile;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+/// Cross-process lock on a path in the cache, released when dropped. Every lock opens its own
+/// handle to the lock file, so it also excludes other threads of the same process.
+pub struct CacheLock {
+    _file: LockFile,
+}
+
+impl CacheLock {
+    pub fn acquire(path: &Path) -> Result<Self> {
+        let locks_dir = get_cache_dir()?.join("locks");
+        fs::create_dir_all(&locks_dir)?;
+        let hash = format!("{:x}", Sha256::digest(path.to_string_lossy().as_bytes()));
+        let mut file = LockFile::open(&locks_dir.join(format!("{}.lock", &hash[..16])))?;
+        if !file.try_lock()? {
+            debug!(
+                "Waiting for another rgl process to release <d>{}</>",
+                path.display()
+            );
+            file.lock()?;
+        }
+        Ok(Self { _file: file })
+    }
+}
+
+/// Written to the filter cache directory, records which projects used the filter and when.
+pub const USAGE_INFO: &str = ".rgl-usage.json";
+
+#[derive(Default, Serialize, Deserialize)]
+struct Usage {
+    /// Project directory → last used, in seconds since the Unix epoch
+    projects: BTreeMap<String, u64>,
+}
+
+/// Record that the current project used the filter installed in `filter_dir`. Failures are only
+/// logged, usage tracking must never break a build.
+pub fn record_usage(filter_dir: &Path) {
+    let inner = || -> Result<()> {
+        let project = get_current_dir()?;
+        if !project.join("config.json").is_file() {
+            return Ok(());
+        }
+        let path = filter_dir.join(USAGE_INFO);
+        // Projects sharing the cache may run the filter at the same time
+        let _lock = CacheLock::acquire(&path)?;
+        let mut usage: Usage = read_json(&path).unwrap_or_default();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        usage.projects.insert(project.display().to_string(), now);
+        // Replace the file at once so `rgl cache` never reads a partial file
+        let partial = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+        write_json(&partial, &usage)?;
+        fs::rename(partial, path)?;
+        Ok(())
+    };
+    if let Err(e) = inner() {
+        debug!("Failed to record filter usage: {e}");
+    }
+}
+
+/// A filter version installed in the cache.
+pub struct CachedFilter {
+    pub path: PathBuf,
+    pub url: String,
+    pub name: String,
+    pub version: String,
+    pub size: u64,
+    /// Projects that used this filter, most recent first
+    pub projects: Vec<(String, SystemTime)>,
+    pub installed: SystemTime,
+}
+
+impl CachedFilter {
+    fn load(path: &Path, filters_dir: &Path) -> Self {
+        let relative: Vec<_> = path
+            .strip_prefix(filters_dir)
+            .unwrap_or(path)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        let (url, rest) = relative.split_at(relative.len().saturating_sub(2));
+        let usage: Usage = read_json(path.join(USAGE_INFO)).unwrap_or_default();
+        let mut projects: Vec<_> = usage
+            .projects
+            .into_iter()
+            .map(|(project, time)| (project, UNIX_EPOCH + Duration::from_secs(time)))
+            .collect();
+        projects.sort_by_key(|(_, time)| Reverse(*time));
+        let installed = path
+            .join(INSTALL_INFO)
+            .metadata()
+            .or_else(|_| path.metadata())
+            .and_then(|metadata| metadata.modified())
+            .unwrap_or(UNIX_EPOCH);
+        Self {
+            path: path.to_owned(),
+            url: url.join("/"),
+            name: rest.first().cloned().unwrap_or_default(),
+            version: rest.get(1).cloned().unwrap_or_default(),
+            size: dir_size(path),
+            projects,
+            installed,
+        }
+    }
+
+    /// Directory of the repository this filter was installed from, relative to the repo cache.
+    pub fn repo_path(&self) -> PathBuf {
+        self.url.split('/').collect()
+    }
+
+    /// Most recent use by a project that still exists, or the install time if it was never used.
+    pub fn last_used(&self) -> SystemTime {
+        if self.projects.is_empty() {
+            return self.installed;
+        }
+        self.projects
+            .iter()
+            .filter(|(project, _)| Path::new(project).join("config.json").is_file())
+            .map(|(_, time)| *time)
+            .max()
+            .unwrap_or(UNIX_EPOCH)
+    }
+
+    /// Problems with the installed files, e.g. an interrupted install or modified files.
+    pub fn verify(&self) -> Result<Vec<String>> {
+        let mut problems = vec![];
+        if !self.path.join("filter.json").is_file() {
+            problems.push("Missing filter.json".to_owned());
+        }
+        match read_json::<InstallInfo>(self.path.join(INSTALL_INFO)) {
+            Ok(info) => {
+                if info.content_hash(&self.path)? != info.hash {
+                    problems.push("Files were modified after installing".to_owned());
+                }
+            }
+            Err(_) => problems.push(format!("Incomplete install, missing {INSTALL_INFO}")),
+        }
+        Ok(problems)
+    }
+}
+
+/// Every filter version in the cache.
+pub fn cached_filters() -> Result<Vec<CachedFilter>> {
+    let filters_dir = get_cache_dir()?.join("filters");
+    let mut filters = vec![];
+    // Versions are at least `<host>/<name>/<version>` deep
+    let mut walker = WalkDir::new(&filters_dir).min_depth(3).into_iter();
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if entry.file_type().is_dir() && is_filter_dir(entry.path()) {
+            walker.skip_current_dir();
+            filters.push(CachedFilter::load(entry.path(), &filters_dir));
+        }
+    }
+    Ok(filters)
+}
+
+fn is_filter_dir(path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    [INSTALL_INFO, USAGE_INFO, "filter.json"]
+        .iter()
+        .any(|file| path.join(file).is_file())
+        || Version::parse(&name).is_ok()
+        || is_commit(&name)
+}
+
+/// Cached repositories, relative to the repo cache directory.
+pub fn cached_repos() -> Result<Vec<PathBuf>> {
+    let repo_dir = get_repo_cache_dir()?;
+    let mut repos = vec![];
+    let mut walker = WalkDir::new(&repo_dir).min_depth(1).into_iter();
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let path = entry.path();
+        if entry.file_type().is_dir()
+            && path.join("HEAD").is_file()
+            && path.join("objects").is_dir()
+        {
+            walker.skip_current_dir();
+            repos.push(path.strip_prefix(&repo_dir)?.to_owned());
+        }
+    }
+    Ok(repos)
+}
+
+/// Repositories of the configured resolvers, relative to the repo cache directory.
+pub fn resolver_repos() -> HashSet<PathBuf> {
+    UserConfig::resolvers()
+        .iter()
+        .filter_map(|url| match ResolverSource::parse(url) {
+            Ok(ResolverSource::Git { url, .. }) => Some(git_cache_path(&url)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Cached Go builds and when they were last used, see [`super::FilterGo`].
+pub fn cached_go_builds() -> Result<Vec<(PathBuf, SystemTime)>> {
+    let Ok(entries) = get_go_cache_dir()?.read_dir() else {
+        return Ok(vec![]);
+    };
+    let mut builds = vec![];
+    for entry in entries {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let last_used = path
+            .join(".last_used")
+            .metadata()
+            .or_else(|_| path.metadata())
+            .and_then(|metadata| metadata.modified())?;
+        builds.push((path, last_used));
+    }
+    Ok(builds)
+}
+
+/// Cache directories of the global filters and the filters of the current project, including
+/// their dependencies. These are never pruned.
+pub fn referenced_filters() -> Result<HashSet<PathBuf>> {
+    let mut paths = HashSet::new();
+    for (name, filter) in GlobalFilters::load()?.iter() {
+        add_referenced(&mut paths, name, filter)?;
+    }
+    if Path::new("./config.json").is_file() {
+        for (name, filter) in Config::load()?.get_filters()? {
+            if let FilterDefinition::Remote(filter) = filter {
+                add_referenced(&mut paths, &name, &filter)?;
+            }
+        }
+    }
+    Ok(paths)
+}
+
+fn add_referenced(paths: &mut HashSet<PathBuf>, name: &str, filter: &RemoteFilter) -> Result<()> {
+    // Already visited, also prevents looping on dependency cycles
+    if !paths.insert(get_filter_cache_dir(name, filter)?) {
+        return Ok(());
+    }
+    for (dep_name, dep) in filter.dependencies(name)? {
+        add_referenced(paths, &dep_name, &dep)?;
+    }
+    Ok(())
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+pub fn format_age(time: SystemTime) -> String {
+    let secs = time.elapsed().unwrap_or_default().as_secs();
+    match secs {
+        0..60 => "just now".to_owned(),
+        60..3600 => format!("{} minute(s) ago", secs / 60),
+        3600..86400 => format!("{} hour(s) ago", secs / 3600),
+        _ => format!("{} day(s) ago", secs / 86400),
+    }
+}
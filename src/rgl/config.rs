@@ -1,6 +1,6 @@
 use super::{
-    DevelopmentExport, Export, FilterDefinition, FilterRunner, LocalExport, Profile, ProfileEntry,
-    RemoteFilter, UserConfig,
+    ArgumentOrder, DevelopmentExport, Export, FilterDefinition, FilterRunner, GlobalFilters,
+    LocalExport, Lockfile, PathFilter, Profile, ProfileEntry, RemoteFilter, UserConfig,
 };
 use crate::file_watcher::FileWatcher;
 use crate::fs::{read_json, write_file, write_json};
@@ -38,6 +38,9 @@ struct Regolith {
     data_path: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     watch_paths: Option<Vec<String>>,
+    /// Default argument order for remote filters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    argument_order: Option<ArgumentOrder>,
     filter_definitions: BTreeMap<String, Value>,
     profiles: IndexMap<String, Profile>,
 }
@@ -75,6 +78,7 @@ impl Config {
             regolith: Regolith {
                 data_path: "./data".to_owned(),
                 watch_paths: None,
+                argument_order: None,
                 filter_definitions: BTreeMap::<String, Value>::new(),
                 profiles,
             },
@@ -116,6 +120,10 @@ impl Config {
             .with_context(|| format!("Profile <profile>{profile_name}</> not found"))
     }
 
+    pub fn get_profiles(&self) -> &IndexMap<String, Profile> {
+        &self.regolith.profiles
+    }
+
     pub fn get_filter(&self, filter_name: &str) -> Result<FilterDefinition> {
         let value = self
             .regolith
@@ -123,30 +131,74 @@ impl Config {
             .get(filter_name)
             .with_context(|| {
                 format!("Filter <filter>{filter_name}</> is not defined in filterDefinitions")
-            })?
-            .to_owned();
-        FilterDefinition::from_value(value).map_err(|e| {
+            })?;
+        self.parse_filter(filter_name, value, &Lockfile::load()?)
+    }
+
+    /// Filter referenced by a profile. `global:<name>` refers to a filter installed with
+    /// `rgl install`, `use_global` falls back to one when the name isn't in filterDefinitions.
+    /// Returns the name of the filter without the prefix.
+    pub fn get_profile_filter(
+        &self,
+        filter_name: &str,
+        use_global: bool,
+    ) -> Result<(String, FilterDefinition)> {
+        let (name, global) = match filter_name.strip_prefix("global:") {
+            Some(name) => (name, true),
+            None if self.regolith.filter_definitions.contains_key(filter_name) => {
+                return Ok((filter_name.to_owned(), self.get_filter(filter_name)?));
+            }
+            None => (filter_name, use_global),
+        };
+        if !global {
+            return Ok((name.to_owned(), self.get_filter(name)?));
+        }
+        let filter = GlobalFilters::load()?.get(name).map_err(|_| {
             anyhow!(
-                "Invalid filter definition for <filter>{filter_name}</>\n\
-                 <yellow> >></> {e}"
+                "Global filter <filter>{name}</> is not installed\n\
+                 <yellow> >></> Run `rgl install {name}` to install it"
             )
-        })
+        })?;
+        Ok((name.to_owned(), filter.into()))
     }
 
     pub fn get_filters(&self) -> Result<BTreeMap<String, FilterDefinition>> {
+        let lockfile = Lockfile::load()?;
         let mut filters = BTreeMap::<String, FilterDefinition>::new();
         for (name, value) in &self.regolith.filter_definitions {
-            let filter = FilterDefinition::from_value(value.to_owned()).map_err(|e| {
-                anyhow!(
-                    "Invalid filter definition for <filter>{name}</>\n\
-                     <yellow> >></> {e}"
-                )
-            })?;
-            filters.insert(name.to_owned(), filter);
+            filters.insert(name.to_owned(), self.parse_filter(name, value, &lockfile)?);
         }
         Ok(filters)
     }
 
+    /// Versions ranges of remote filters use the locked version, see [`RemoteFilter::apply_lock`].
+    fn parse_filter(
+        &self,
+        filter_name: &str,
+        value: &Value,
+        lockfile: &Lockfile,
+    ) -> Result<FilterDefinition> {
+        let mut filter = FilterDefinition::from_value(value.to_owned()).map_err(|e| {
+            anyhow!(
+                "Invalid filter definition for <filter>{filter_name}</>\n\
+                 <yellow> >></> {e}"
+            )
+        })?;
+        match &mut filter {
+            FilterDefinition::Remote(RemoteFilter { argument_order, .. })
+            | FilterDefinition::Path(PathFilter { argument_order, .. }) => {
+                if argument_order.is_none() {
+                    *argument_order = self.regolith.argument_order;
+                }
+            }
+            FilterDefinition::Local(_) => {}
+        }
+        if let FilterDefinition::Remote(remote) = &mut filter {
+            remote.apply_lock(filter_name, lockfile);
+        }
+        Ok(filter)
+    }
+
     pub fn get_watcher(&self) -> Result<FileWatcher> {
         let mut watcher = FileWatcher::new()?;
 
@@ -163,6 +215,11 @@ impl Config {
                 watcher.add_path(path)?;
             }
         }
+        for filter in self.get_filters()?.values() {
+            if let FilterDefinition::Path(filter) = filter {
+                watcher.add_path(&filter.path)?;
+            }
+        }
 
         Ok(watcher)
     }
@@ -194,12 +251,25 @@ impl ConfigCst {
     }
 
     pub fn add_filter(&self, filter_name: &str, remote: RemoteFilter) {
+        let version = remote.version_spec();
         let url = remote.url;
-        let version = remote.version;
-        let value = json!({ "url": url, "version": version });
         if let Some(definition) = self.filter_definitions.get(filter_name) {
-            definition.set_value(value);
+            // Update in place to keep other properties, e.g. `argumentOrder`
+            match definition.object_value() {
+                Some(object) if object.get("url").is_some() => {
+                    for (key, value) in [("url", url), ("version", version)] {
+                        match object.get(key) {
+                            Some(prop) => prop.set_value(json!(value)),
+                            None => {
+                                object.append(key, json!(value));
+                            }
+                        }
+                    }
+                }
+                _ => definition.set_value(json!({ "url": url, "version": version })),
+            }
         } else {
+            let value = json!({ "url": url, "version": version });
             let index = self
                 .filter_definitions
                 .properties()
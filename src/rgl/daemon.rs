@@ -0,0 +1,204 @@
+use super::{print_output, FilterContext, MessageHandler, Subprocess, UserConfig};
+use crate::fs::is_ignored_entry;
+use crate::{debug, info};
+use anyhow::{anyhow, bail, Context, Result};
+use dashmap::DashMap;
+use serde_json::{json, Value};
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, LazyLock, Mutex,
+    },
+    thread,
+};
+use walkdir::WalkDir;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static CHANGED_PATHS: Mutex<Vec<PathBuf>> = Mutex::new(vec![]);
+/// Filter name and script, remote filters can define several persistent filters
+type DaemonKey = (String, String);
+static DAEMONS: LazyLock<DashMap<DaemonKey, Arc<Mutex<Option<FilterDaemon>>>>> =
+    LazyLock::new(DashMap::new);
+
+/// A filter process that is kept alive across rebuilds in `rgl watch`.
+///
+/// Filters opt in with `"persistent": true`. The process is started once with the
+/// `RGL_PERSISTENT` env variable set, and receives a JSON-RPC request per line on stdin:
+///
+/// ```json
+/// {"jsonrpc": "2.0", "id": 1, "method": "run", "params": {"tempDir": "...", "settings": {}, "arguments": [], "changedPaths": []}}
+/// ```
+///
+/// It must reply with a single line containing either `result` or `error` for the same `id`.
+/// Any other output is handled the same way as one-shot filters.
+pub struct FilterDaemon {
+    child: Child,
+    stdin: ChildStdin,
+    responses: mpsc::Receiver<(Value, usize)>,
+    fingerprint: u64,
+    next_id: u64,
+}
+
+impl FilterDaemon {
+    pub fn enable() {
+        ENABLED.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled() -> bool {
+        ENABLED.load(Ordering::Relaxed)
+    }
+
+    /// Paths that changed since the previous run, sent to the daemons on the next run.
+    pub fn set_changed_paths(paths: Vec<PathBuf>) {
+        *CHANGED_PATHS.lock().unwrap() = paths;
+    }
+
+    /// Runs the filter through its daemon, starting it if needed. The subprocess is only
+    /// used when the daemon has to be (re)started.
+    pub fn run(
+        context: &FilterContext,
+        script: &str,
+        subprocess: Subprocess,
+        temp: &Path,
+        run_args: &[String],
+    ) -> Result<()> {
+        let name = &context.name;
+        let entry = DAEMONS
+            .entry((name.to_owned(), script.to_owned()))
+            .or_default()
+            .clone();
+        let mut daemon = entry.lock().unwrap();
+
+        let fingerprint = fingerprint(&context.filter_dir.join(context.filter_dir(script)));
+        if let Some(current) = daemon.as_mut() {
+            if !current.is_alive() {
+                info!("Filter <filter>{name}</> exited, restarting...");
+                *daemon = None;
+            } else if current.fingerprint != fingerprint {
+                info!("Filter <filter>{name}</> changed, restarting...");
+                *daemon = None;
+            }
+        }
+        let current = match daemon.as_mut() {
+            Some(current) => current,
+            None => daemon.insert(Self::spawn(name, subprocess, fingerprint)?),
+        };
+
+        // Settings are passed separately, so skip them from the arguments
+        let arguments = match context.settings {
+            Some(_) => run_args.get(1..).unwrap_or_default(),
+            None => run_args,
+        };
+        let params = json!({
+            "tempDir": dunce::canonicalize(temp)?,
+            "filterDir": context.filter_dir,
+            "settings": context.settings,
+            "arguments": arguments,
+            "changedPaths": *CHANGED_PATHS.lock().unwrap(),
+        });
+        let result = current.request("run", params);
+        if result.is_err() && !current.is_alive() {
+            *daemon = None;
+        }
+        result
+    }
+
+    fn spawn(name: &str, mut subprocess: Subprocess, fingerprint: u64) -> Result<Self> {
+        debug!("Starting persistent filter <filter>{name}</>");
+        let mut child = subprocess.env("RGL_PERSISTENT", "1").spawn()?;
+        let stdin = child.stdin.take().expect("stdin piped");
+        let stdout = child.stdout.take().expect("stdout piped");
+        let stderr = child.stderr.take().expect("stderr piped");
+        let prefix = UserConfig::subprocess_logging().then(|| name.to_owned());
+
+        let (tx, responses) = mpsc::channel();
+        let mut handler = MessageHandler::new(name);
+        let prefix_out = prefix.to_owned();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if let Ok(value) = serde_json::from_str::<Value>(&line) {
+                    if value.get("jsonrpc").is_some() && value.get("id").is_some() {
+                        let _ = tx.send((value, handler.error_count));
+                        handler.error_count = 0;
+                        continue;
+                    }
+                }
+                if !handler.handle(&line) {
+                    print_output(prefix_out.as_deref(), &line, false);
+                }
+            }
+        });
+        thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                print_output(prefix.as_deref(), &line, true);
+            }
+        });
+
+        Ok(Self {
+            child,
+            stdin,
+            responses,
+            fingerprint,
+            next_id: 1,
+        })
+    }
+
+    fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Result<()> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        writeln!(self.stdin, "{request}")
+            .and_then(|_| self.stdin.flush())
+            .context("Failed sending request to persistent filter")?;
+        loop {
+            let (response, error_count) = self
+                .responses
+                .recv()
+                .map_err(|_| anyhow!("Persistent filter exited before responding"))?;
+            if response["id"] != id {
+                continue;
+            }
+            if let Some(error) = response.get("error") {
+                let message = error["message"].as_str().unwrap_or("Unknown error");
+                bail!("Filter responded with an error: {message}");
+            }
+            if error_count > 0 {
+                bail!("Filter reported {error_count} error(s)");
+            }
+            return Ok(());
+        }
+    }
+}
+
+impl Drop for FilterDaemon {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Cheap fingerprint of the filter files, used to restart the daemon when they change.
+fn fingerprint(dir: &Path) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| !is_ignored_entry(entry))
+        .filter_map(|entry| entry.ok())
+        .for_each(|entry| {
+            entry.path().hash(&mut hasher);
+            if let Ok(metadata) = entry.metadata() {
+                metadata.len().hash(&mut hasher);
+                metadata.modified().ok().hash(&mut hasher);
+            }
+        });
+    hasher.finish()
+}
@@ -1,22 +1,27 @@
 use super::{
     get_current_dir, get_filter_cache_dir, FilterBun, FilterDeno, FilterExe, FilterGo,
-    FilterNodejs, FilterPython, FilterShell, RemoteFilter, RemoteFilterConfig,
+    FilterNodejs, FilterPython, FilterShell, PathFilter, RemoteFilter, RemoteFilterConfig, Sandbox,
+    UserConfig,
 };
 use crate::fs::{is_dir_empty, read_json};
 use crate::info;
-use anyhow::{Context, Result};
+use crate::logger::format_problems;
+use anyhow::{bail, Context, Result};
 use enum_dispatch::enum_dispatch;
-use serde::{Deserialize, Serialize};
+use indexmap::IndexMap;
+use serde::{de, Deserialize, Deserializer, Serialize};
 use serde_json::Value;
+use std::fs;
 use std::path::{Path, PathBuf};
 use strum::Display;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize)]
 #[serde(untagged)]
 #[enum_dispatch]
 pub enum FilterDefinition {
     Local(LocalFilter),
     Remote(RemoteFilter),
+    Path(PathFilter),
 }
 
 impl FilterDefinition {
@@ -26,6 +31,10 @@ impl FilterDefinition {
                 let filter = serde_json::from_value::<LocalFilter>(value)?;
                 FilterDefinition::Local(filter)
             }
+            _ if value.get("path").is_some() => {
+                let filter = serde_json::from_value::<PathFilter>(value)?;
+                FilterDefinition::Path(filter)
+            }
             _ => {
                 let filter = serde_json::from_value::<RemoteFilter>(value)?;
                 FilterDefinition::Remote(filter)
@@ -35,6 +44,13 @@ impl FilterDefinition {
     }
 }
 
+impl<'de> Deserialize<'de> for FilterDefinition {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Self::from_value(value).map_err(de::Error::custom)
+    }
+}
+
 #[derive(Display, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "runWith")]
 #[enum_dispatch]
@@ -51,7 +67,10 @@ pub enum LocalFilter {
 pub struct FilterContext {
     pub name: String,
     pub filter_dir: PathBuf,
+    pub version: Option<String>,
     pub remote_config: Option<RemoteFilterConfig>,
+    /// Settings from the profile entry, also passed as the first run argument
+    pub settings: Option<IndexMap<String, Value>>,
 }
 
 impl FilterContext {
@@ -60,7 +79,9 @@ impl FilterContext {
             FilterDefinition::Local(_) => Ok(Self {
                 name: name.to_owned(),
                 filter_dir: get_current_dir()?,
+                version: None,
                 remote_config: None,
+                settings: None,
             }),
             FilterDefinition::Remote(remote) => {
                 let filter_dir = get_filter_cache_dir(name, remote)?;
@@ -75,7 +96,25 @@ impl FilterContext {
                 Ok(Self {
                     name: name.to_owned(),
                     filter_dir,
+                    version: Some(remote.version.to_owned()),
+                    remote_config,
+                    settings: None,
+                })
+            }
+            FilterDefinition::Path(filter) => {
+                let filter_dir = dunce::canonicalize(&filter.path).with_context(|| {
+                    format!("Failed to find filter directory <b>{}</>", filter.path)
+                })?;
+                let remote_config =
+                    read_json(filter_dir.join("filter.json")).with_context(|| {
+                        format!("Failed to load config for filter <filter>{name}</>")
+                    })?;
+                Ok(Self {
+                    name: name.to_owned(),
+                    filter_dir,
+                    version: None,
                     remote_config,
+                    settings: None,
                 })
             }
         }
@@ -90,6 +129,48 @@ impl FilterContext {
             dir
         }
     }
+
+    /// Check that the system meets the requirements declared by the remote filter.
+    pub fn check_requirements(&self) -> Result<()> {
+        match &self.remote_config {
+            Some(config) => config.requirements.check(&self.name),
+            None => Ok(()),
+        }
+    }
+
+    /// Validate the settings against the schema provided by the remote filter.
+    pub fn validate_settings(&self) -> Result<()> {
+        let Some(config) = &self.remote_config else {
+            return Ok(());
+        };
+        let problems = config.validate_settings(&self.filter_dir, self.settings.as_ref())?;
+        if problems.is_empty() {
+            return Ok(());
+        }
+        bail!(
+            "Invalid settings for filter <filter>{}</>\n{}",
+            self.name,
+            format_problems(&problems)
+        )
+    }
+
+    /// Project directory where the filter can keep files between runs, writable when sandboxed.
+    pub fn cache_dir(&self) -> Result<PathBuf> {
+        let cache_dir = get_current_dir()?
+            .join(".regolith")
+            .join("cache")
+            .join(&self.name);
+        fs::create_dir_all(&cache_dir)?;
+        Ok(cache_dir)
+    }
+
+    /// Remote filters run in a sandbox when enabled in the user config.
+    pub fn sandbox(&self, temp: &Path) -> Result<Option<Sandbox>> {
+        if !UserConfig::sandbox() || self.remote_config.is_none() {
+            return Ok(None);
+        }
+        Sandbox::new(self, temp)
+    }
 }
 
 #[enum_dispatch(FilterDefinition, LocalFilter)]
@@ -100,3 +181,8 @@ pub trait Filter {
         Ok(())
     }
 }
+
+/// For `skip_serializing_if` on boolean options that default to `false`.
+pub fn is_false(value: &bool) -> bool {
+    !value
+}
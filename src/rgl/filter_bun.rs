@@ -1,4 +1,4 @@
-use super::{Filter, FilterContext, Subprocess, UserConfig};
+use super::{Filter, FilterContext, Subprocess};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -17,13 +17,9 @@ impl Filter for FilterBun {
             .arg(script)
             .args(run_args)
             .current_dir(temp)
-            .setup_env(&context.filter_dir);
-        if UserConfig::subprocess_logging() {
-            subprocess.run_with_prefix(&context.name)?;
-        } else {
-            subprocess.run()?;
-        }
-        Ok(())
+            .setup_env(&context.filter_dir)
+            .sandbox(context.sandbox(temp)?);
+        subprocess.run_filter(&context.name)
     }
 
     fn install_dependencies(&self, context: &FilterContext) -> Result<()> {
@@ -1,11 +1,29 @@
-use super::{Filter, FilterContext, Subprocess, UserConfig};
+use super::{is_false, temp_write_dirs, Filter, FilterContext, Subprocess};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct FilterDeno {
     pub script: String,
+    /// Grants every permission, same as `deno run -A`
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub allow_all: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_read: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_write: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_env: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_net: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_run: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_sys: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_ffi: Option<Vec<String>>,
 }
 
 impl Filter for FilterDeno {
@@ -13,16 +31,93 @@ impl Filter for FilterDeno {
         let script = context.filter_dir.join(&self.script);
         let mut subprocess = Subprocess::new("deno");
         subprocess
-            .args(vec!["run", "-A", "--no-lock"])
+            .arg("run")
+            .args(self.permission_args(context, temp)?)
+            .args(self.config_args(context))
             .arg(script)
             .args(run_args)
             .current_dir(temp)
-            .setup_env(&context.filter_dir);
-        if UserConfig::subprocess_logging() {
-            subprocess.run_with_prefix(&context.name)?;
+            .setup_env(&context.filter_dir)
+            .sandbox(context.sandbox(temp)?);
+        subprocess.run_filter(&context.name)
+    }
+
+    fn install_dependencies(&self, context: &FilterContext) -> Result<()> {
+        let script = context.filter_dir.join(&self.script);
+        Subprocess::new("deno")
+            .arg("cache")
+            .args(self.config_args(context))
+            .arg(script)
+            .run()?;
+        Ok(())
+    }
+}
+
+impl FilterDeno {
+    /// Permission flags for `deno run`. By default the filter can only read and write the temp
+    /// directory, read its own directory, and read the env variables set by rgl.
+    fn permission_args(&self, context: &FilterContext, temp: &Path) -> Result<Vec<String>> {
+        if self.allow_all {
+            return Ok(vec!["-A".to_owned()]);
+        }
+        let temp_paths = temp_write_dirs(temp)?;
+
+        let mut read = temp_paths.to_owned();
+        read.push(context.filter_dir.to_owned());
+        let read = join_paths(read, &self.allow_read);
+        let write = join_paths(temp_paths, &self.allow_write);
+        let mut env = vec!["FILTER_DIR".to_owned(), "ROOT_DIR".to_owned()];
+        env.extend(self.allow_env.iter().flatten().cloned());
+
+        let mut args = vec![
+            format!("--allow-read={read}"),
+            format!("--allow-write={write}"),
+            format!("--allow-env={}", env.join(",")),
+        ];
+        for (flag, values) in [
+            ("--allow-net", &self.allow_net),
+            ("--allow-run", &self.allow_run),
+            ("--allow-sys", &self.allow_sys),
+            ("--allow-ffi", &self.allow_ffi),
+        ] {
+            match values {
+                // An empty list grants the permission without restrictions
+                Some(values) if values.is_empty() => args.push(flag.to_owned()),
+                Some(values) => args.push(format!("{flag}={}", values.join(","))),
+                None => {}
+            }
+        }
+        Ok(args)
+    }
+
+    /// Uses `deno.json` and `deno.lock` from the filter directory when present.
+    fn config_args(&self, context: &FilterContext) -> Vec<String> {
+        let filter_dir = context.filter_dir.join(context.filter_dir(&self.script));
+        let mut args = vec![];
+        if let Some(config) = ["deno.json", "deno.jsonc"]
+            .iter()
+            .map(|name| filter_dir.join(name))
+            .find(|path| path.is_file())
+        {
+            args.push(format!("--config={}", config.display()));
+        }
+        let lock = filter_dir.join("deno.lock");
+        if lock.is_file() {
+            args.push(format!("--lock={}", lock.display()));
         } else {
-            subprocess.run()?;
+            args.push("--no-lock".to_owned());
         }
-        Ok(())
+        args
     }
 }
+
+/// Joins the default paths with the user provided ones. User paths are relative to the temp
+/// directory, same as the filter's working directory.
+fn join_paths(defaults: Vec<PathBuf>, extra: &Option<Vec<String>>) -> String {
+    defaults
+        .iter()
+        .map(|path| path.display().to_string())
+        .chain(extra.iter().flatten().cloned())
+        .collect::<Vec<_>>()
+        .join(",")
+}
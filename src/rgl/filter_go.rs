@@ -1,8 +1,20 @@
-use super::{Filter, FilterContext, Subprocess, UserConfig};
-use anyhow::Result;
+use super::{cached_go_builds, get_go_cache_dir, Filter, FilterContext, Subprocess};
+use crate::debug;
+use crate::fs::{is_ignored_entry, rimraf, write_file};
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::{env, path::Path};
-use walkdir::{DirEntry, WalkDir};
+use sha2::{Digest, Sha256};
+use std::{
+    env::consts::{ARCH, OS},
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+/// Builds that haven't been used for this long are removed from the cache.
+const MAX_BUILD_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
 
 #[derive(Serialize, Deserialize)]
 pub struct FilterGo {
@@ -12,35 +24,37 @@ pub struct FilterGo {
 impl Filter for FilterGo {
     fn run(&self, context: &FilterContext, temp: &Path, run_args: &[String]) -> Result<()> {
         let script = context.filter_dir.join(&self.script);
-        let mut output = env::current_dir()?
-            .join(".regolith")
-            .join("cache")
-            .join("go")
-            .join(&context.name);
+        let hash = hash_sources(context, &self.script)?;
+        let build_dir = get_go_cache_dir()?.join(&hash);
+        let mut output = build_dir.join(&context.name);
         if cfg!(windows) {
             output.set_extension("exe");
         }
 
-        if should_rebuild(&context.filter_dir, &output)? {
+        if !output.is_file() {
+            debug!("Building <filter>{}</> ({hash})", context.name);
+            fs::create_dir_all(&build_dir)?;
+            // Build to a unique path first so concurrent builds never expose a partial binary
+            let mut partial = build_dir.join(Uuid::new_v4().to_string());
+            partial.set_extension(output.extension().unwrap_or_default());
             Subprocess::new("go")
                 .args(vec!["build", "-o"])
-                .arg(&output)
+                .arg(&partial)
                 .arg(script)
                 .current_dir(&context.filter_dir)
                 .run()?;
+            fs::rename(&partial, &output).context("Failed to move Go build output")?;
+            remove_stale_builds(&build_dir)?;
         }
+        write_file(build_dir.join(".last_used"), "")?;
 
         let mut subprocess = Subprocess::new(output);
         subprocess
             .args(run_args)
             .current_dir(temp)
-            .setup_env(&context.filter_dir);
-        if UserConfig::subprocess_logging() {
-            subprocess.run_with_prefix(&context.name)?;
-        } else {
-            subprocess.run()?;
-        }
-        Ok(())
+            .setup_env(&context.filter_dir)
+            .sandbox(context.sandbox(temp)?);
+        subprocess.run_filter(&context.name)
     }
 
     fn install_dependencies(&self, context: &FilterContext) -> Result<()> {
@@ -52,27 +66,67 @@ impl Filter for FilterGo {
     }
 }
 
-fn should_rebuild(path: &Path, output: &Path) -> Result<bool> {
-    let output_time = if let Ok(metadata) = output.metadata() {
-        metadata.modified()?
-    } else {
-        return Ok(true);
-    };
-    let walker = WalkDir::new(path)
+/// Hash everything that affects the build output: the Go sources of the whole module, including
+/// packages imported from elsewhere in it, `go.mod`/`go.sum`, filter version, and the target
+/// platform.
+fn hash_sources(context: &FilterContext, script: &str) -> Result<String> {
+    let module_dir = module_root(context, script);
+    let mut files: Vec<PathBuf> = WalkDir::new(&module_dir)
         .into_iter()
-        .filter_entry(|entry| !is_hidden(entry));
-    for entry in walker {
-        if entry?.metadata()?.modified()? > output_time {
-            return Ok(true);
-        }
+        .filter_entry(|entry| !is_ignored_entry(entry))
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file() && is_go_source(entry.path()))
+        .map(|entry| entry.into_path())
+        .collect();
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for path in files {
+        hasher.update(
+            path.strip_prefix(&context.filter_dir)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .as_bytes(),
+        );
+        hasher.update(fs::read(&path)?);
     }
-    Ok(false)
+    hasher.update(context.version.as_deref().unwrap_or_default());
+    hasher.update(OS);
+    hasher.update(ARCH);
+    let hash = format!("{:x}", hasher.finalize());
+    Ok(hash[..16].to_owned())
+}
+
+/// Directory of the `go.mod` closest to the script. Local filters may share a module defined in
+/// the project root, the search stops at the filter directory.
+fn module_root(context: &FilterContext, script: &str) -> PathBuf {
+    let script_dir = context.filter_dir.join(context.filter_dir(script));
+    script_dir
+        .ancestors()
+        .take_while(|dir| dir.starts_with(&context.filter_dir))
+        .find(|dir| dir.join("go.mod").is_file())
+        .unwrap_or(&context.filter_dir)
+        .to_owned()
 }
 
-fn is_hidden(entry: &DirEntry) -> bool {
-    entry
-        .file_name()
-        .to_str()
-        .map(|s| s.starts_with('.'))
-        .unwrap_or(false)
+fn is_go_source(path: &Path) -> bool {
+    match path.file_name().and_then(|name| name.to_str()) {
+        Some("go.mod" | "go.sum") => true,
+        _ => path.extension().is_some_and(|ext| ext == "go"),
+    }
+}
+
+/// Remove builds that haven't been used recently, except the current one.
+fn remove_stale_builds(current: &Path) -> Result<()> {
+    let now = SystemTime::now();
+    for (path, last_used) in cached_go_builds()? {
+        if path == current {
+            continue;
+        }
+        if now.duration_since(last_used).unwrap_or_default() > MAX_BUILD_AGE {
+            debug!("Removing stale Go build <d>{}</>", path.display());
+            rimraf(path)?;
+        }
+    }
+    Ok(())
 }
@@ -0,0 +1,29 @@
+use super::{with_dependency_stack, ArgumentOrder, Filter, FilterContext};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A filter loaded from a local directory containing a `filter.json`, run the same way as
+/// remote filters. Useful when developing filters that will be published.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PathFilter {
+    pub path: String,
+    /// Overrides the project's `argumentOrder`
+    #[serde(rename = "argumentOrder", skip_serializing_if = "Option::is_none")]
+    pub argument_order: Option<ArgumentOrder>,
+}
+
+impl Filter for PathFilter {
+    fn run(&self, context: &FilterContext, temp: &Path, run_args: &[String]) -> Result<()> {
+        let config = context.remote_config.as_ref().unwrap();
+        let key = context.filter_dir.display().to_string();
+        with_dependency_stack(key, || {
+            config.run(context, temp, run_args, self.argument_order)
+        })
+    }
+    fn install_dependencies(&self, context: &FilterContext) -> Result<()> {
+        let config = context.remote_config.as_ref().unwrap();
+        config.install_remote_dependencies(&context.name, None, false)?;
+        config.install_dependencies(context)
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Filter, FilterContext, Subprocess, UserConfig};
+use super::{Filter, FilterContext, FilterDaemon, Subprocess, UserConfig};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -6,6 +6,9 @@ use std::path::Path;
 #[derive(Serialize, Deserialize)]
 pub struct FilterPython {
     pub script: String,
+    /// Keep the filter running between rebuilds in `rgl watch`
+    #[serde(default)]
+    pub persistent: bool,
 }
 
 impl Filter for FilterPython {
@@ -22,15 +25,13 @@ impl Filter for FilterPython {
         subprocess
             .arg("-u")
             .arg(script)
-            .args(run_args)
             .current_dir(temp)
-            .setup_env(&context.filter_dir);
-        if UserConfig::subprocess_logging() {
-            subprocess.run_with_prefix(&context.name)?;
-        } else {
-            subprocess.run()?;
+            .setup_env(&context.filter_dir)
+            .sandbox(context.sandbox(temp)?);
+        if self.persistent && FilterDaemon::is_enabled() {
+            return FilterDaemon::run(context, &self.script, subprocess, temp, run_args);
         }
-        Ok(())
+        subprocess.args(run_args).run_filter(&context.name)
     }
 
     fn install_dependencies(&self, context: &FilterContext) -> Result<()> {
@@ -1,24 +1,152 @@
+use super::{download_archive, extract_filter, verify_checksum};
 use super::{
-    get_filter_cache_dir, get_repo_cache_dir, Eval, Filter, FilterContext, LocalFilter, Resolver,
-    Subprocess,
+    ensure_online, get_filter_cache_dir, get_repo_cache_dir, get_staging_dir, git_cache_path,
+    git_clone_url, git_export_dir, git_fetch, git_resolve_commit, is_offline, record_usage,
+    CacheLock, Eval, Filter, FilterContext, FilterDefinition, LockedFilter, Lockfile, Requirements,
+    Resolver,
 };
-use crate::fs::{copy_dir, empty_dir, is_dir_empty, rimraf};
+use crate::fs::{copy_dir, hash_dir, hash_files, list_files, read_json, rimraf, write_json};
 use crate::{debug, info, warn};
-use anyhow::{bail, Context, Result};
-use semver::Version;
+use anyhow::{anyhow, bail, Context, Result};
+use indexmap::IndexMap;
+use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::fs;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use uuid::Uuid;
+
+thread_local! {
+    /// Remote filters currently being installed or run, used to detect dependency cycles
+    static DEPENDENCY_STACK: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+}
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct RemoteFilter {
     pub url: String,
     pub version: String,
+    /// Overrides the project's `argumentOrder`
+    #[serde(rename = "argumentOrder", skip_serializing_if = "Option::is_none")]
+    pub argument_order: Option<ArgumentOrder>,
+    /// Version range from the config, `version` holds the concrete version it resolved to
+    #[serde(skip)]
+    pub range: Option<VersionReq>,
+}
+
+/// Written to the filter cache directory after installing, records what was installed.
+pub const INSTALL_INFO: &str = ".rgl-install.json";
+
+#[derive(Serialize, Deserialize)]
+pub struct InstallInfo {
+    /// Unknown when installed from an archive that doesn't record it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Content hash of the filter files, see [`hash_dir`]
+    pub hash: String,
+    /// Files that were installed. Files created later, e.g. `package-lock.json` by a dependency
+    /// install, are not part of the hash.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+}
+
+impl InstallInfo {
+    fn new(commit: Option<String>, tag: Option<String>, filter_dir: &Path) -> Result<Self> {
+        let files = list_files(filter_dir);
+        Ok(Self {
+            commit,
+            tag,
+            hash: hash_files(filter_dir, &files)?,
+            files,
+        })
+    }
+
+    /// Hash of the installed files as they are now, comparable to [`Self::hash`].
+    pub fn content_hash(&self, filter_dir: &Path) -> Result<String> {
+        // Installed by an older version of rgl
+        if self.files.is_empty() {
+            return hash_dir(filter_dir);
+        }
+        hash_files(filter_dir, &self.files)
+    }
+}
+
+/// Order of the arguments passed to the filters defined in `filter.json`.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArgumentOrder {
+    /// `[settings, parent_args, remote_args]`
+    #[default]
+    Rgl,
+    /// `[settings, remote_args, parent_args]`, same as Regolith
+    Regolith,
 }
 
 impl Filter for RemoteFilter {
     fn run(&self, context: &FilterContext, temp: &Path, run_args: &[String]) -> Result<()> {
         let config = context.remote_config.as_ref().unwrap();
-        for entry in &config.filters {
+        record_usage(&context.filter_dir);
+        with_dependency_stack(self.key(&context.name), || {
+            config.run(context, temp, run_args, self.argument_order)
+        })
+    }
+    fn install_dependencies(&self, context: &FilterContext) -> Result<()> {
+        let config = context.remote_config.as_ref().unwrap();
+        config.install_dependencies(context)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RemoteFilterConfig {
+    #[serde(default, rename = "exportData")]
+    pub export_data: bool,
+    /// Allow network access when running in a sandbox
+    #[serde(default)]
+    pub network: bool,
+    /// JSON Schema for the profile settings, either inline or a path relative to the filter
+    #[serde(rename = "settingsSchema", skip_serializing_if = "Option::is_none")]
+    pub settings_schema: Option<Value>,
+    #[serde(flatten)]
+    pub requirements: Requirements,
+    pub filters: Vec<RemoteFilterEntry>,
+}
+
+impl RemoteFilterConfig {
+    /// Run the filters defined in `filter.json`. Shared by remote and path filters.
+    pub fn run(
+        &self,
+        context: &FilterContext,
+        temp: &Path,
+        run_args: &[String],
+        argument_order: Option<ArgumentOrder>,
+    ) -> Result<()> {
+        let order = argument_order.unwrap_or_default();
+        // Settings always come first, only the remaining arguments are reordered
+        let (settings, parent_args) = match context.settings {
+            Some(_) => run_args.split_at(run_args.len().min(1)),
+            None => run_args.split_at(0),
+        };
+        // Only warn when the order was not chosen explicitly. Dependencies never receive the
+        // profile arguments, so only local entries are affected.
+        if argument_order.is_none()
+            && !parent_args.is_empty()
+            && self.filters.iter().any(|entry| {
+                matches!(entry.filter, FilterDefinition::Local(_)) && entry.arguments.is_some()
+            })
+        {
+            warn!(
+                "Filter <filter>{}</> receives arguments from both the profile and its filter.json. \
+                 rgl passes the profile arguments first, unlike Regolith. Set `argumentOrder` to \
+                 `regolith` if the filter expects Regolith's ordering",
+                context.name
+            );
+        }
+        for entry in &self.filters {
             if let Some(expression) = &entry.expression {
                 let name = &context.name;
                 let eval = Eval::new(name, &context.filter_dir, None);
@@ -30,107 +158,341 @@ impl Filter for RemoteFilter {
                     continue;
                 }
             }
-            // This behavior is different from Regolith. It might break some filters
-            // that need the arguments to be passed in a specific order.
-            // Regolith: [settings, remote_args, parent_args]
-            // rgl: [settings, parent_args, remote_args]
-            let mut run_args = run_args.to_vec();
-            if let Some(arguments) = entry.arguments.to_owned() {
-                run_args.extend(arguments);
-            };
-            entry.filter.run(context, temp, &run_args)?;
+            let remote_args = entry.arguments.as_deref().unwrap_or_default();
+            match &entry.filter {
+                FilterDefinition::Local(filter) => {
+                    let run_args = match order {
+                        ArgumentOrder::Rgl => [settings, parent_args, remote_args].concat(),
+                        ArgumentOrder::Regolith => [settings, remote_args, parent_args].concat(),
+                    };
+                    filter.run(context, temp, &run_args)?;
+                }
+                // Dependencies only receive the settings and arguments from the entry
+                FilterDefinition::Remote(_) => {
+                    let name = entry.dependency_name()?;
+                    let mut run_args = vec![];
+                    if let Some(settings) = &entry.settings {
+                        run_args.push(serde_json::to_string(settings)?);
+                    }
+                    run_args.extend(remote_args.iter().cloned());
+                    let mut dep_context = FilterContext::new(name, &entry.filter)?;
+                    dep_context.settings = entry.settings.to_owned();
+                    dep_context.check_requirements()?;
+                    dep_context.validate_settings()?;
+                    entry
+                        .filter
+                        .run(&dep_context, temp, &run_args)
+                        .with_context(|| format!("Failed running filter <filter>{name}</>"))?;
+                }
+                FilterDefinition::Path(_) => {
+                    bail!("Path filters can't be referenced from filter.json")
+                }
+            }
         }
         Ok(())
     }
-    fn install_dependencies(&self, context: &FilterContext) -> Result<()> {
-        let config = context.remote_config.as_ref().unwrap();
-        for entry in &config.filters {
-            entry.filter.install_dependencies(context)?;
+
+    /// Install dependencies of the local filters defined in `filter.json`.
+    pub fn install_dependencies(&self, context: &FilterContext) -> Result<()> {
+        for entry in &self.filters {
+            // Remote dependencies are installed separately by `install_remote_dependencies`
+            if let FilterDefinition::Local(filter) = &entry.filter {
+                filter.install_dependencies(context)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Install the remote filters referenced by `filter.json`.
+    pub fn install_remote_dependencies(
+        &self,
+        name: &str,
+        data_path: Option<&Path>,
+        force: bool,
+    ) -> Result<()> {
+        for (dep_name, dep) in self.dependencies()? {
+            info!("Installing <filter>{dep_name}</> required by <filter>{name}</>...");
+            dep.install(dep_name, data_path, force)?;
         }
         Ok(())
     }
+
+    /// Remote filters referenced by the entries of this filter.
+    pub fn dependencies(&self) -> Result<Vec<(&str, &RemoteFilter)>> {
+        let mut dependencies = vec![];
+        for entry in &self.filters {
+            if let FilterDefinition::Remote(filter) = &entry.filter {
+                let name = entry.dependency_name()?;
+                // Only the project lockfile pins ranges, dependencies would change silently
+                if parse_version_range(&filter.version).is_some() {
+                    bail!(
+                        "Dependency <filter>{name}</> uses the version range `{}`\n\
+                         <yellow> >></> Dependencies must use an exact version",
+                        filter.version
+                    );
+                }
+                dependencies.push((name, filter));
+            }
+        }
+        Ok(dependencies)
+    }
+
+    /// Validate profile settings against the settings schema, returning the problems found.
+    pub fn validate_settings(
+        &self,
+        filter_dir: &Path,
+        settings: Option<&IndexMap<String, Value>>,
+    ) -> Result<Vec<String>> {
+        let schema = match &self.settings_schema {
+            Some(Value::String(path)) => read_json(filter_dir.join(path))?,
+            Some(schema) => schema.to_owned(),
+            None => return Ok(vec![]),
+        };
+        let validator = jsonschema::validator_for(&schema)
+            .map_err(|e| anyhow!("Invalid settings schema: {e}"))?;
+        let instance = match settings {
+            Some(settings) => serde_json::to_value(settings)?,
+            None => json!({}),
+        };
+        let problems = validator
+            .iter_errors(&instance)
+            .map(|e| format!("{}: {e}", to_json_path(&e.instance_path.to_string())))
+            .collect();
+        Ok(problems)
+    }
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct RemoteFilterConfig {
-    #[serde(default, rename = "exportData")]
-    pub export_data: bool,
-    pub filters: Vec<RemoteFilterEntry>,
+/// Convert a JSON pointer (`/foo/0`) to a readable path (`settings.foo[0]`).
+fn to_json_path(pointer: &str) -> String {
+    let mut path = "settings".to_owned();
+    for segment in pointer.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        if segment.parse::<usize>().is_ok() {
+            path.push_str(&format!("[{segment}]"));
+        } else {
+            path.push_str(&format!(".{segment}"));
+        }
+    }
+    path
 }
 
+/// A filter defined in `filter.json`. Either a local filter, or another remote filter:
+///
+/// ```json
+/// { "name": "other", "url": "github.com/user/repo", "version": "1.0.0", "settings": {} }
+/// ```
 #[derive(Serialize, Deserialize)]
 pub struct RemoteFilterEntry {
+    /// Name of the filter, required when referencing another remote filter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
     pub arguments: Option<Vec<String>>,
+    /// Settings passed to a remote filter dependency
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings: Option<IndexMap<String, Value>>,
     #[serde(rename = "when", skip_serializing_if = "Option::is_none")]
     pub expression: Option<String>,
     #[serde(flatten)]
-    pub filter: LocalFilter,
+    pub filter: FilterDefinition,
+}
+
+impl RemoteFilterEntry {
+    fn dependency_name(&self) -> Result<&str> {
+        self.name
+            .as_deref()
+            .context("Remote filter entries in filter.json require a `name`")
+    }
 }
 
 impl RemoteFilter {
     /// Parse RemoteFilter from string argument
     pub fn parse(arg: &str) -> Result<(String, Self)> {
-        // Extract version argument if present
-        let parts: Vec<_> = arg.split('@').collect();
-        let (arg, version_arg) = match parts.len() {
-            1 => (parts[0], None),
-            2 => (parts[0], Some(parts[1].to_owned())),
-            _ => bail!("Invalid argument <b>{arg}</>"),
+        // Extract version argument if present. URLs like `git@host:path` also contain `@`.
+        let (arg, version_arg) = match arg.rsplit_once('@') {
+            Some((arg, version)) if !version.contains(['/', ':']) => {
+                (arg, Some(version.to_owned()))
+            }
+            _ => (arg, None),
         };
 
-        // Resolve filter name and URL
-        let url_parts: Vec<_> = arg.split('/').collect();
-        let (name, url) = match url_parts.len() {
-            1 => (arg.to_owned(), Resolver::resolve_url(arg)?),
-            4 => (url_parts[3].to_owned(), url_parts[..3].join("/")),
-            _ => bail!("Incorrect URL format. Expected: `github.com/<user>/<repo>/<filter-name>`"),
+        // Resolve filter name and URL, the filter name is the last path segment
+        let (name, url) = match arg.rsplit_once('/') {
+            None => (arg.to_owned(), Resolver::resolve_url(arg)?),
+            Some((url, name)) if !url.is_empty() && !name.is_empty() => {
+                (name.to_owned(), url.to_owned())
+            }
+            _ => bail!(
+                "Incorrect URL format. Expected: `<git-url>/<filter-name>`, \
+                 e.g. `github.com/<user>/<repo>/<filter-name>`"
+            ),
         };
 
+        let range = version_arg.as_deref().and_then(parse_version_range);
         let version = Resolver::resolve_version(&name, &url, version_arg)?;
         info!("Resolved <b>{arg}</> to <b>{url}/{name}@{version}</>");
 
-        Ok((name, Self { url, version }))
+        Ok((
+            name,
+            Self {
+                url,
+                version,
+                argument_order: None,
+                range,
+            },
+        ))
+    }
+
+    /// If the version is a range, e.g. `^1.4`, use the locked version as long as it satisfies the
+    /// range. Ranges that aren't locked yet are resolved by [`Self::resolve_range`].
+    pub fn apply_lock(&mut self, name: &str, lockfile: &Lockfile) {
+        let Some(range) = parse_version_range(&self.version) else {
+            return;
+        };
+        let locked = lockfile.get(name).filter(|locked| {
+            locked.url == self.url
+                && Version::parse(&locked.version).is_ok_and(|version| range.matches(&version))
+        });
+        if let Some(locked) = locked {
+            self.version = locked.version.to_owned();
+        }
+        self.range = Some(range);
+    }
+
+    /// Whether the version is a range that isn't locked yet.
+    pub fn is_unresolved(&self) -> bool {
+        self.range.is_some() && Version::parse(&self.version).is_err()
+    }
+
+    /// Resolve a range that isn't locked yet to the newest matching version. Requires network
+    /// access, so only done before installing or running the filter.
+    pub fn resolve_range(&mut self, name: &str) -> Result<()> {
+        if let (true, Some(range)) = (self.is_unresolved(), &self.range) {
+            self.version = Resolver::resolve_version(name, &self.url, Some(range.to_string()))
+                .with_context(|| {
+                    format!("Failed to resolve version of filter <filter>{name}</>")
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Version as written in the config, the range if there is one.
+    pub fn version_spec(&self) -> String {
+        match &self.range {
+            Some(range) => range.to_string(),
+            None => self.version.to_owned(),
+        }
+    }
+
+    /// Identifies a specific version of the filter in dependency chains.
+    fn key(&self, name: &str) -> String {
+        format!("{}/{name}@{}", self.url, self.version)
+    }
+
+    /// Remote filters this filter depends on. Returns nothing if it is not installed yet.
+    pub fn dependencies(&self, name: &str) -> Result<Vec<(String, RemoteFilter)>> {
+        let config_path = get_filter_cache_dir(name, self)?.join("filter.json");
+        if !config_path.is_file() {
+            return Ok(vec![]);
+        }
+        let config: RemoteFilterConfig = read_json(config_path)?;
+        let dependencies = config
+            .dependencies()?
+            .into_iter()
+            .map(|(name, filter)| (name.to_owned(), filter.to_owned()))
+            .collect();
+        Ok(dependencies)
     }
 
     pub fn install(&self, name: &str, data_path: Option<&Path>, force: bool) -> Result<()> {
-        let url = &self.url;
+        with_dependency_stack(self.key(name), || {
+            self.install_inner(name, data_path, force, None)
+        })
+    }
+
+    /// Install the exact commit recorded in the lockfile, and verify the installed content.
+    pub fn install_locked(
+        &self,
+        name: &str,
+        data_path: Option<&Path>,
+        force: bool,
+        locked: &LockedFilter,
+    ) -> Result<()> {
+        let filter_dir = get_filter_cache_dir(name, self)?;
+        with_dependency_stack(self.key(name), || {
+            self.install_inner(name, data_path, force, Some(locked))
+        })?;
+        let info = self
+            .install_info(name)?
+            .with_context(|| format!("Filter <filter>{name}</> is not installed"))?;
+        let hash = info.content_hash(&filter_dir)?;
+        if hash != locked.hash {
+            bail!(
+                "Content of filter <filter>{name}</> does not match rgl.lock\n\
+                 <yellow> >></> Expected: {}\n\
+                 <yellow> >></> Found: {hash}",
+                locked.hash
+            );
+        }
+        Ok(())
+    }
+
+    /// Information about the installed copy of the filter, if any.
+    pub fn install_info(&self, name: &str) -> Result<Option<InstallInfo>> {
+        let path = get_filter_cache_dir(name, self)?.join(INSTALL_INFO);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        read_json(path)
+    }
+
+    /// Lockfile entry for the installed copy of the filter.
+    pub fn lock(&self, name: &str) -> Result<LockedFilter> {
+        let info = self
+            .install_info(name)?
+            .with_context(|| format!("Filter <filter>{name}</> is not installed"))?;
+        Ok(LockedFilter {
+            url: self.url.to_owned(),
+            version: self.version.to_owned(),
+            tag: info.tag,
+            commit: info.commit,
+            hash: info.hash,
+        })
+    }
+
+    fn install_inner(
+        &self,
+        name: &str,
+        data_path: Option<&Path>,
+        force: bool,
+        locked: Option<&LockedFilter>,
+    ) -> Result<()> {
         let version = &self.version;
         let filter_dir = get_filter_cache_dir(name, self)?;
+        let commit = locked.and_then(|locked| locked.commit.as_deref());
+        // Other threads or rgl processes may be installing the same filter
+        let lock = CacheLock::acquire(&filter_dir)?;
 
-        if force {
-            rimraf(&filter_dir)?;
-        }
-        let https_url = format!("https://{url}");
-        if is_dir_empty(&filter_dir)? {
-            let repo_dir = get_repo_cache_dir()?.join(url);
-            if is_dir_empty(&repo_dir)? {
-                empty_dir(&repo_dir)?;
-                debug!("Cloning repo: {https_url}");
-                Subprocess::new("git")
-                    .args(["clone", &https_url, "."])
-                    .current_dir(&repo_dir)
-                    .run_silent()
-                    .with_context(|| format!("Failed to clone `{https_url}`"))?;
-            } else {
-                debug!("Fetching tags...");
-                Subprocess::new("git")
-                    .args(["fetch", "--all"])
-                    .current_dir(&repo_dir)
-                    .run_silent()
-                    .with_context(|| format!("Failed to fetch latest tags from `{https_url}`"))?;
+        // Reinstall if the installed files don't match the lock
+        let modified = match (locked, self.install_info(name)?) {
+            (Some(locked), Some(info)) => {
+                info.commit != locked.commit || info.content_hash(&filter_dir)? != locked.hash
+            }
+            _ => false,
+        };
+        // Filters installed by older versions of rgl don't have install info
+        if force || modified || !filter_dir.join(INSTALL_INFO).is_file() {
+            // Offline installs need the cached repository, keep the installed files otherwise
+            if is_offline()
+                && !get_repo_cache_dir()?
+                    .join(git_cache_path(&self.url))
+                    .is_dir()
+            {
+                ensure_online(&format!("Filter <filter>{name}</> <b>{version}</>"))?;
             }
-            let git_ref = Version::parse(version)
-                .map(|_| format!("{name}-{version}"))
-                .unwrap_or(version.to_owned());
-            debug!("Checkout ref: {git_ref}");
-            Subprocess::new("git")
-                .args(["checkout", &git_ref])
-                .current_dir(&repo_dir)
-                .run_silent()
-                .with_context(|| format!("Failed to checkout `{git_ref}`"))?;
-            copy_dir(repo_dir.join(name), &filter_dir)?;
+            self.install_files(name, commit, &filter_dir)?;
         }
         if let Some(data_path) = data_path {
+            record_usage(&filter_dir);
             let filter_data = filter_dir.join("data");
             let target_path = data_path.join(name);
             if filter_data.is_dir() && !target_path.exists() {
@@ -138,19 +500,155 @@ impl RemoteFilter {
                 copy_dir(filter_data, target_path)?;
             }
         }
+        // Dependencies may be installed by another thread, avoid holding the lock meanwhile
+        drop(lock);
 
         let filter = self.to_owned().into();
         let context = FilterContext::new(name, &filter)?;
+        context.check_requirements()?;
+        if let Some(config) = &context.remote_config {
+            config.install_remote_dependencies(name, data_path, force)?;
+        }
+        let _lock = CacheLock::acquire(&filter_dir)?;
         info!("Installing dependencies for <filter>{name}</>...");
         filter.install_dependencies(&context)
     }
 
-    pub fn update(&mut self, name: &str, data_path: Option<&Path>, force: bool) -> Result<()> {
+    /// Install into a staging directory, then move it into place. The cache never contains a
+    /// partially installed filter, even if rgl is interrupted.
+    fn install_files(&self, name: &str, commit: Option<&str>, filter_dir: &Path) -> Result<()> {
+        let version = &self.version;
+        let tag = Version::parse(version)
+            .ok()
+            .map(|_| format!("{name}-{version}"));
+        let staging_dir = get_staging_dir()?.join(Uuid::new_v4().to_string());
+        let install = || -> Result<()> {
+            let info = match self.install_archive(name, tag.as_deref(), commit, &staging_dir)? {
+                Some(info) => info,
+                None => self.install_git(name, tag.as_deref(), commit, &staging_dir)?,
+            };
+            write_json(staging_dir.join(INSTALL_INFO), &info)?;
+            if filter_dir.exists() {
+                // Move the previous install out of the way first, renaming is atomic
+                let old_dir = get_staging_dir()?.join(Uuid::new_v4().to_string());
+                fs::rename(filter_dir, &old_dir)?;
+                rimraf(old_dir)?;
+            }
+            if let Some(parent) = filter_dir.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(&staging_dir, filter_dir)
+                .context("Failed to move the installed filter into the cache")?;
+            Ok(())
+        };
+        install().inspect_err(|_| {
+            let _ = rimraf(&staging_dir);
+        })
+    }
+
+    /// Install from a release archive provided by the resolver, or a GitHub tag archive.
+    /// Returns `None` if no archive is available.
+    fn install_archive(
+        &self,
+        name: &str,
+        tag: Option<&str>,
+        commit: Option<&str>,
+        filter_dir: &Path,
+    ) -> Result<Option<InstallInfo>> {
+        if is_offline() {
+            return Ok(None);
+        }
+        let git_ref = commit.or(tag).unwrap_or(&self.version);
+        let resolved = tag.and_then(|_| Resolver::resolve_archive(name, &self.url, &self.version));
+        let (archive_url, checksum) = match resolved {
+            Some(archive) => archive,
+            None => match github_archive_url(&self.url, git_ref) {
+                Some(archive_url) => (archive_url, None),
+                None => return Ok(None),
+            },
+        };
+        let Some(bytes) = download_archive(&archive_url) else {
+            debug!("No archive available, falling back to git");
+            return Ok(None);
+        };
+        if let Some(checksum) = checksum {
+            verify_checksum(&bytes, &checksum)
+                .with_context(|| format!("Failed to verify archive `{archive_url}`"))?;
+        }
+        let is_zip = archive_url.ends_with(".zip");
+        let archive_commit = match extract_filter(&bytes, is_zip, name, filter_dir) {
+            Ok(commit) => commit,
+            Err(e) => {
+                warn!("Failed to extract archive `{archive_url}`, falling back to git");
+                debug!("{e}");
+                rimraf(filter_dir)?;
+                return Ok(None);
+            }
+        };
+        let commit = commit.map(str::to_owned).or(archive_commit);
+        Ok(Some(InstallInfo::new(
+            commit,
+            tag.map(str::to_owned),
+            filter_dir,
+        )?))
+    }
+
+    fn install_git(
+        &self,
+        name: &str,
+        tag: Option<&str>,
+        commit: Option<&str>,
+        filter_dir: &Path,
+    ) -> Result<InstallInfo> {
+        let clone_url = git_clone_url(&self.url);
+        let repo_dir = get_repo_cache_dir()?.join(git_cache_path(&self.url));
+        debug!("Fetching repo: {clone_url}");
+        let repo = git_fetch(&clone_url, &repo_dir)?;
+        let git_ref = commit.or(tag).unwrap_or(&self.version);
+        debug!("Checkout ref: {git_ref}");
+        let commit = git_resolve_commit(&repo, git_ref)?;
+        git_export_dir(&repo, commit, name, filter_dir)
+            .inspect_err(|_| {
+                let _ = rimraf(filter_dir);
+            })
+            .with_context(|| format!("Failed to checkout `{git_ref}`"))?;
+        InstallInfo::new(Some(commit.to_string()), tag.map(str::to_owned), filter_dir)
+    }
+
+    /// Version argument that resolves to the newest version `rgl update` would install without
+    /// `--major`: within the range, or within the current major version for exact versions.
+    pub fn update_constraint(&self) -> String {
+        match (&self.range, Version::parse(&self.version)) {
+            (Some(range), _) => range.to_string(),
+            (None, Ok(version)) => format!("^{version}"),
+            (None, Err(_)) => "latest".to_owned(),
+        }
+    }
+
+    /// Update to the newest version within the range, or within the current major version for
+    /// exact versions. `major` allows crossing major versions, widening the range if needed.
+    pub fn update(
+        &mut self,
+        name: &str,
+        data_path: Option<&Path>,
+        force: bool,
+        major: bool,
+    ) -> Result<()> {
         let current_version = self.version.to_owned();
-        let latest_version = Resolver::resolve_version(name, &self.url, Some("latest".to_owned()))?;
+        let version_arg = match major {
+            true => "latest".to_owned(),
+            false => self.update_constraint(),
+        };
+        let latest_version = Resolver::resolve_version(name, &self.url, Some(version_arg))?;
+        if let (Some(range), Ok(version)) = (&self.range, Version::parse(&latest_version)) {
+            if !range.matches(&version) {
+                self.range = Some(VersionReq::parse(&format!("^{version}"))?);
+            }
+        }
         if current_version == latest_version {
             warn!("Filter <filter>{name}</> is already up-to-date");
-            return Ok(());
+            // Still needed for the lockfile, e.g. in a fresh clone
+            return self.install(name, data_path, force);
         }
         info!("Updating filter <filter>{name}</> <cyan>{current_version}</> → <cyan>{latest_version}</>...");
         self.version = latest_version.to_owned();
@@ -158,3 +656,87 @@ impl RemoteFilter {
         Ok(())
     }
 }
+
+/// Install filters on `jobs` worker threads, logging `[n/N]` progress as each one finishes.
+/// Returns the filters with their lockfile entries, in the original order. Stops picking up new
+/// filters after the first failure.
+pub fn install_filters(
+    jobs: usize,
+    filters: Vec<(String, RemoteFilter)>,
+    install: impl Fn(&str, &mut RemoteFilter) -> Result<()> + Sync,
+) -> Result<Vec<(String, RemoteFilter, LockedFilter)>> {
+    let total = filters.len();
+    let queue = Mutex::new(filters.into_iter().enumerate());
+    let results = Mutex::new(Vec::with_capacity(total));
+    let done = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    // Plain threads instead of rayon, so a worker waiting on a nested parallel operation can't
+    // pick up another filter while holding its locks
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(total) {
+            scope.spawn(|| {
+                while !failed.load(Ordering::Relaxed) {
+                    let Some((index, (name, mut remote))) = queue.lock().unwrap().next() else {
+                        break;
+                    };
+                    let result = install(&name, &mut remote)
+                        .and_then(|_| remote.lock(&name))
+                        .map(|locked| {
+                            let n = done.fetch_add(1, Ordering::Relaxed) + 1;
+                            info!(
+                                "[{n}/{total}] Filter <filter>{name}</> <cyan>{}</> is ready",
+                                remote.version
+                            );
+                            (name, remote, locked)
+                        });
+                    if result.is_err() {
+                        failed.store(true, Ordering::Relaxed);
+                    }
+                    results.lock().unwrap().push((index, result));
+                }
+            });
+        }
+    });
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Parses versions like `^1.4`, `~2.1.0` or `>=1.2, <2`. Exact versions, `latest`, `HEAD` and
+/// commit hashes are not ranges.
+pub fn parse_version_range(version: &str) -> Option<VersionReq> {
+    if Version::parse(version).is_ok() {
+        return None;
+    }
+    VersionReq::parse(version).ok()
+}
+
+/// Runs `f` with the filter pushed to the dependency stack, failing if it is already there.
+pub fn with_dependency_stack<T>(key: String, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    DEPENDENCY_STACK.with_borrow_mut(|stack| {
+        if let Some(index) = stack.iter().position(|k| *k == key) {
+            let mut cycle = stack[index..].to_vec();
+            cycle.push(key.to_owned());
+            bail!("Dependency cycle detected: {}", cycle.join(" → "));
+        }
+        stack.push(key);
+        Ok(())
+    })?;
+    let result = f();
+    DEPENDENCY_STACK.with_borrow_mut(|stack| stack.pop());
+    result
+}
+
+/// Tag or commit archive for repositories hosted on GitHub.
+fn github_archive_url(url: &str, git_ref: &str) -> Option<String> {
+    let path = git_clone_url(url);
+    let path = path
+        .strip_prefix("https://github.com/")?
+        .trim_end_matches(".git");
+    match path.split('/').collect::<Vec<_>>()[..] {
+        [owner, repo] => Some(format!(
+            "https://codeload.github.com/{owner}/{repo}/tar.gz/{git_ref}"
+        )),
+        _ => None,
+    }
+}
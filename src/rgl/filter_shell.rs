@@ -1,28 +1,139 @@
-use super::{Filter, FilterContext, Subprocess, UserConfig};
-use anyhow::Result;
+use super::{Filter, FilterContext, Subprocess};
+use anyhow::{bail, Result};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::{io::Write, path::Path};
+use strum::Display;
+use tempfile::TempPath;
 
 #[derive(Serialize, Deserialize)]
 pub struct FilterShell {
-    pub command: String,
+    #[serde(flatten)]
+    pub default: ShellVariant,
+    /// Overrides applied when running on the matching OS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub windows: Option<ShellVariant>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub linux: Option<ShellVariant>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub macos: Option<ShellVariant>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct ShellVariant {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell: Option<Shell>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Script file relative to the filter directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+}
+
+#[derive(Clone, Copy, Display, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Shell {
+    Sh,
+    Bash,
+    Zsh,
+    Fish,
+    Pwsh,
+    Powershell,
+    Cmd,
 }
 
 impl Filter for FilterShell {
     fn run(&self, context: &FilterContext, temp: &Path, run_args: &[String]) -> Result<()> {
-        let shell = if cfg!(windows) { "powershell" } else { "sh" };
-        let mut subprocess = Subprocess::new(shell);
+        let variant = self.current_variant();
+        let shell = variant.shell.unwrap_or(match cfg!(windows) {
+            true => Shell::Powershell,
+            false => Shell::Sh,
+        });
+        let mut subprocess = Subprocess::new(shell.to_string());
+        // Keeps the generated script alive until the process exits
+        let _script_file;
+        match (&variant.command, &variant.script) {
+            (Some(command), None) => match shell {
+                Shell::Sh | Shell::Bash | Shell::Zsh => {
+                    // The first argument after the command becomes `$0`
+                    subprocess.arg("-c").arg(command).arg(&context.name);
+                }
+                Shell::Fish => {
+                    subprocess.arg("-c").arg(command);
+                }
+                Shell::Pwsh | Shell::Powershell | Shell::Cmd => {
+                    // These shells don't accept positional parameters for inline commands, so the
+                    // command is written to a script file instead. The filter's cache directory
+                    // stays accessible when sandboxed.
+                    let script = write_script(shell, command, &context.cache_dir()?)?;
+                    shell_script_args(shell, &mut subprocess, &script);
+                    _script_file = script;
+                }
+            },
+            (None, Some(script)) => {
+                let script = context.filter_dir.join(script);
+                shell_script_args(shell, &mut subprocess, &script);
+            }
+            (Some(_), Some(_)) => bail!("Shell filter can't have both `command` and `script`"),
+            (None, None) => bail!("Shell filter requires either `command` or `script`"),
+        }
         subprocess
-            .arg("-c")
-            .arg(&self.command)
             .args(run_args)
             .current_dir(temp)
-            .setup_env(&context.filter_dir);
-        if UserConfig::subprocess_logging() {
-            subprocess.run_with_prefix(&context.name)?;
-        } else {
-            subprocess.run()?;
+            .setup_env(&context.filter_dir)
+            .sandbox(context.sandbox(temp)?);
+        subprocess.run_filter(&context.name)
+    }
+}
+
+impl FilterShell {
+    /// Merge the OS specific variant into the default one.
+    fn current_variant(&self) -> ShellVariant {
+        let os_variant = match std::env::consts::OS {
+            "windows" => &self.windows,
+            "linux" => &self.linux,
+            "macos" => &self.macos,
+            _ => &None,
+        };
+        let mut variant = self.default.clone();
+        if let Some(os_variant) = os_variant {
+            if os_variant.shell.is_some() {
+                variant.shell = os_variant.shell;
+            }
+            if os_variant.command.is_some() || os_variant.script.is_some() {
+                variant.command = os_variant.command.to_owned();
+                variant.script = os_variant.script.to_owned();
+            }
         }
-        Ok(())
+        variant
     }
 }
+
+fn shell_script_args(shell: Shell, subprocess: &mut Subprocess, script: &Path) {
+    match shell {
+        Shell::Sh | Shell::Bash | Shell::Zsh | Shell::Fish => {
+            subprocess.arg(script);
+        }
+        Shell::Pwsh | Shell::Powershell => {
+            subprocess
+                .args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-File"])
+                .arg(script);
+        }
+        Shell::Cmd => {
+            subprocess.args(["/D", "/C"]).arg(script);
+        }
+    }
+}
+
+fn write_script(shell: Shell, command: &str, dir: &Path) -> Result<TempPath> {
+    let suffix = match shell {
+        Shell::Cmd => ".cmd",
+        _ => ".ps1",
+    };
+    let mut file = tempfile::Builder::new()
+        .prefix("rgl-")
+        .suffix(suffix)
+        .tempfile_in(dir)?;
+    file.write_all(command.as_bytes())?;
+    Ok(file.into_temp_path())
+}
@@ -0,0 +1,108 @@
+use super::{get_filter_cache_dir, Config, FilterDefinition, RemoteFilter};
+use crate::fs::{read_json, write_json};
+use crate::logger::format_problems;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
+
+const LOCKFILE_PATH: &str = "./rgl.lock";
+
+/// Records the exact version of every remote filter installed in the project.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Lockfile {
+    filters: BTreeMap<String, LockedFilter>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LockedFilter {
+    pub url: String,
+    pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    pub hash: String,
+}
+
+impl LockedFilter {
+    /// Whether the entry was locked for the same filter definition.
+    pub fn matches(&self, remote: &RemoteFilter) -> bool {
+        self.url == remote.url && self.version == remote.version
+    }
+}
+
+impl Lockfile {
+    pub fn load() -> Result<Self> {
+        if !Path::new(LOCKFILE_PATH).exists() {
+            return Ok(Self::default());
+        }
+        read_json(LOCKFILE_PATH)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        write_json(LOCKFILE_PATH, self)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LockedFilter> {
+        self.filters.get(name)
+    }
+
+    pub fn insert(&mut self, name: &str, filter: LockedFilter) {
+        self.filters.insert(name.to_owned(), filter);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<LockedFilter> {
+        self.filters.remove(name)
+    }
+
+    /// Remove entries of filters that are no longer remote filters in the config.
+    pub fn retain(&mut self, config: &Config) -> Result<()> {
+        let filters = config.get_filters()?;
+        self.filters
+            .retain(|name, _| matches!(filters.get(name), Some(FilterDefinition::Remote(_))));
+        Ok(())
+    }
+
+    /// Fails if the lockfile doesn't match the remote filters in the config, or the installed
+    /// filters.
+    pub fn verify(&self, config: &Config) -> Result<()> {
+        let filters = config.get_filters()?;
+        let mut problems = vec![];
+        for (name, filter) in &filters {
+            let FilterDefinition::Remote(remote) = filter else {
+                continue;
+            };
+            match self.filters.get(name) {
+                None => problems.push(format!("Filter <filter>{name}</> is not locked")),
+                Some(locked) if !locked.matches(remote) => problems.push(format!(
+                    "Filter <filter>{name}</> is locked to <b>{}@{}</>, but config.json has <b>{}@{}</>",
+                    locked.url, locked.version, remote.url, remote.version
+                )),
+                Some(locked) => {
+                    if let Some(info) = remote.install_info(name)? {
+                        let hash = info.content_hash(&get_filter_cache_dir(name, remote)?)?;
+                        if info.commit != locked.commit || hash != locked.hash {
+                            problems.push(format!(
+                                "Installed files of filter <filter>{name}</> don't match the lock"
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        for name in self.filters.keys() {
+            if !matches!(filters.get(name), Some(FilterDefinition::Remote(_))) {
+                problems.push(format!(
+                    "Filter <filter>{name}</> is locked, but is not a remote filter in config.json"
+                ));
+            }
+        }
+        if problems.is_empty() {
+            return Ok(());
+        }
+        bail!(
+            "rgl.lock is out of date, run `rgl get` to update it\n{}",
+            format_problems(&problems)
+        )
+    }
+}
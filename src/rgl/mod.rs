@@ -1,4 +1,8 @@
+mod archive;
+mod auth;
+mod cache;
 mod config;
+mod daemon;
 mod eval;
 mod export;
 mod filter;
@@ -7,15 +11,22 @@ mod filter_deno;
 mod filter_exe;
 mod filter_go;
 mod filter_nodejs;
+mod filter_path;
 mod filter_python;
 mod filter_remote;
 mod filter_shell;
+mod git;
 mod global_filters;
+mod lockfile;
 mod minecraft;
+mod offline;
 mod paths;
 mod profile;
+mod protocol;
+mod requirements;
 mod resolver;
 mod runner;
+mod sandbox;
 mod server;
 mod session;
 mod subprocess;
@@ -23,7 +34,11 @@ mod temp;
 mod user_config;
 mod version_check;
 
+pub use self::archive::*;
+pub use self::auth::*;
+pub use self::cache::*;
 pub use self::config::*;
+pub use self::daemon::*;
 pub use self::eval::*;
 pub use self::export::*;
 pub use self::filter::*;
@@ -32,15 +47,22 @@ pub use self::filter_deno::*;
 pub use self::filter_exe::*;
 pub use self::filter_go::*;
 pub use self::filter_nodejs::*;
+pub use self::filter_path::*;
 pub use self::filter_python::*;
 pub use self::filter_remote::*;
 pub use self::filter_shell::*;
+pub use self::git::*;
 pub use self::global_filters::*;
+pub use self::lockfile::*;
 pub use self::minecraft::*;
+pub use self::offline::*;
 pub use self::paths::*;
 pub use self::profile::*;
+pub use self::protocol::*;
+pub use self::requirements::*;
 pub use self::resolver::*;
 pub use self::runner::*;
+pub use self::sandbox::*;
 pub use self::server::*;
 pub use self::session::*;
 pub use self::subprocess::*;
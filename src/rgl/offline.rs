@@ -0,0 +1,24 @@
+use anyhow::{bail, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Disable network access, resolution then only uses cached resolver data and installed filters.
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Fails if network access is disabled, `what` describes what needs to be downloaded.
+pub fn ensure_online(what: &str) -> Result<()> {
+    if is_offline() {
+        bail!(
+            "{what} is not available offline\n\
+             <yellow> >></> Run without `--offline` or `RGL_OFFLINE` to download it"
+        );
+    }
+    Ok(())
+}
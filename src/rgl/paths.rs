@@ -1,4 +1,4 @@
-use super::RemoteFilter;
+use super::{git_cache_path, RemoteFilter};
 use anyhow::Result;
 use once_cell::sync::OnceCell;
 use std::env;
@@ -49,7 +49,7 @@ pub fn get_global_filters_path() -> Result<PathBuf> {
 pub fn get_filter_cache_dir(name: &str, remote: &RemoteFilter) -> Result<PathBuf> {
     Ok(get_cache_dir()?
         .join("filters")
-        .join(&remote.url)
+        .join(git_cache_path(&remote.url))
         .join(name)
         .join(&remote.version))
 }
@@ -61,3 +61,11 @@ pub fn get_repo_cache_dir() -> Result<PathBuf> {
 pub fn get_resolver_cache_dir() -> Result<PathBuf> {
     Ok(get_cache_dir()?.join("resolver"))
 }
+
+pub fn get_staging_dir() -> Result<PathBuf> {
+    Ok(get_cache_dir()?.join("staging"))
+}
+
+pub fn get_go_cache_dir() -> Result<PathBuf> {
+    Ok(get_cache_dir()?.join("go"))
+}
@@ -1,4 +1,4 @@
-use super::{Config, Eval, Export, Filter, FilterContext};
+use super::{is_false, Config, Eval, Export, Filter, FilterContext, FilterDefinition};
 use crate::{debug, info, measure_time};
 use anyhow::{bail, Context, Result};
 use async_recursion::async_recursion;
@@ -31,6 +31,9 @@ pub enum FilterRunner {
     Filter {
         #[serde(rename = "filter")]
         filter_name: String,
+        /// Use the filter installed with `rgl install` when it isn't in filterDefinitions
+        #[serde(rename = "useGlobal", default, skip_serializing_if = "is_false")]
+        use_global: bool,
         #[serde(skip_serializing_if = "Option::is_none")]
         arguments: Option<Vec<String>>,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -62,11 +65,16 @@ impl FilterRunner {
         match self {
             FilterRunner::Filter {
                 filter_name,
+                use_global,
                 arguments,
                 settings,
                 expression,
             } => {
-                let filter = config.get_filter(filter_name)?;
+                let (filter_name, mut filter) =
+                    config.get_profile_filter(filter_name, *use_global)?;
+                if let FilterDefinition::Remote(remote) = &mut filter {
+                    remote.resolve_range(&filter_name)?;
+                }
                 let mut run_args: Vec<String> = vec![];
                 if let Some(settings) = settings {
                     run_args = vec![serde_json::to_string(settings)?]
@@ -75,7 +83,8 @@ impl FilterRunner {
                     run_args.extend(args.iter().map(|x| x.to_owned()));
                 }
 
-                let context = FilterContext::new(filter_name, &filter)?;
+                let mut context = FilterContext::new(&filter_name, &filter)?;
+                context.settings = settings.to_owned();
                 if let Some(expression) = expression {
                     let eval = Eval::new(root_profile, &context.filter_dir, settings.clone());
                     debug!("Evaluating expression: <d>{expression}</>");
@@ -86,6 +95,8 @@ impl FilterRunner {
                         return Ok(export_data_names);
                     }
                 }
+                context.check_requirements()?;
+                context.validate_settings()?;
                 info!("Running filter <filter>{filter_name}</>");
                 filter
                     .run(&context, temp, &run_args)
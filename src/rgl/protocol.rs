@@ -0,0 +1,140 @@
+use crate::{debug, error, info, warn};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+/// Lines printed to stdout starting with this prefix are parsed as JSON messages for rgl.
+///
+/// ```text
+/// ::rgl::{"type": "warning", "message": "Unknown item", "file": "BP/items/a.json", "line": 3}
+/// ```
+pub const MESSAGE_PREFIX: &str = "::rgl::";
+
+static WARNING_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum FilterMessage {
+    Warning {
+        message: String,
+        #[serde(flatten)]
+        location: Location,
+    },
+    Error {
+        message: String,
+        #[serde(flatten)]
+        location: Location,
+    },
+    Progress {
+        message: Option<String>,
+        current: Option<u64>,
+        total: Option<u64>,
+    },
+    SectionStart {
+        name: String,
+    },
+    SectionEnd {
+        name: String,
+    },
+    FilesWritten {
+        files: Vec<String>,
+    },
+}
+
+#[derive(Deserialize)]
+pub struct Location {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+}
+
+impl Location {
+    fn suffix(&self) -> String {
+        let Some(file) = &self.file else {
+            return String::new();
+        };
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => format!(" <d>({file}:{line}:{column})</>"),
+            (Some(line), None) => format!(" <d>({file}:{line})</>"),
+            _ => format!(" <d>({file})</>"),
+        }
+    }
+}
+
+/// Parses and renders messages from a single filter process.
+pub struct MessageHandler {
+    name: String,
+    sections: HashMap<String, Instant>,
+    pub error_count: usize,
+}
+
+impl MessageHandler {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            sections: HashMap::new(),
+            error_count: 0,
+        }
+    }
+
+    /// Returns `false` if the line is not a message and should be printed as is.
+    pub fn handle(&mut self, line: &str) -> bool {
+        let Some(data) = line.trim_end().strip_prefix(MESSAGE_PREFIX) else {
+            return false;
+        };
+        let message = match serde_json::from_str::<FilterMessage>(data) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("[{}] Invalid message from filter: {e}", self.name);
+                return true;
+            }
+        };
+        let name = &self.name;
+        match message {
+            FilterMessage::Warning { message, location } => {
+                WARNING_COUNT.fetch_add(1, Ordering::Relaxed);
+                warn!("[{name}] {message}{}", location.suffix());
+            }
+            FilterMessage::Error { message, location } => {
+                self.error_count += 1;
+                error!("[{name}] {message}{}", location.suffix());
+            }
+            FilterMessage::Progress {
+                message,
+                current,
+                total,
+            } => {
+                let message = message.unwrap_or_default();
+                match (current, total) {
+                    (Some(current), Some(total)) => {
+                        info!("[{name}] {message} <cyan>{current}/{total}</>")
+                    }
+                    (Some(current), None) => info!("[{name}] {message} <cyan>{current}</>"),
+                    _ => info!("[{name}] {message}"),
+                }
+            }
+            FilterMessage::SectionStart { name } => {
+                self.sections.insert(name, Instant::now());
+            }
+            FilterMessage::SectionEnd { name: section } => match self.sections.remove(&section) {
+                Some(start) => {
+                    debug!("{name} > {section}: {}ms", start.elapsed().as_millis())
+                }
+                None => warn!("[{name}] Section <b>{section}</> was never started"),
+            },
+            FilterMessage::FilesWritten { files } => {
+                info!("[{name}] Wrote {} file(s)", files.len());
+                for file in files {
+                    debug!("[{name}] Wrote <d>{file}</>");
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Returns the number of warnings reported by filters and resets the counter.
+pub fn take_warning_count() -> usize {
+    WARNING_COUNT.swap(0, Ordering::Relaxed)
+}
@@ -0,0 +1,141 @@
+use super::UserConfig;
+use crate::logger::format_problems;
+use crate::warn;
+use anyhow::{bail, Result};
+use clap::crate_version;
+use dashmap::DashMap;
+use indexmap::IndexMap;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use std::{env, process, sync::LazyLock};
+
+static RUNTIME_VERSIONS: LazyLock<DashMap<String, Option<Version>>> = LazyLock::new(DashMap::new);
+
+/// Environment a remote filter needs in order to run, declared in its `filter.json`.
+///
+/// ```json
+/// {
+///   "rglVersion": ">=0.18",
+///   "os": ["windows", "linux"],
+///   "arch": ["x86_64"],
+///   "runtimes": { "node": ">=18", "python": ">=3.10" }
+/// }
+/// ```
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Requirements {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rgl_version: Option<VersionReq>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arch: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub runtimes: IndexMap<String, VersionReq>,
+}
+
+impl Requirements {
+    /// Returns an error listing every requirement that is not met.
+    pub fn check(&self, name: &str) -> Result<()> {
+        let mut problems = vec![];
+        if let Some(req) = &self.rgl_version {
+            let version = Version::parse(crate_version!())?;
+            if !req.matches(&version) {
+                problems.push(format!(
+                    "Requires rgl <b>{req}</>, current version is <b>{version}</>"
+                ));
+            }
+        }
+        if let Some(os) = &self.os {
+            if !os.iter().any(|os| os == env::consts::OS) {
+                problems.push(format!(
+                    "Only supports <b>{}</> operating system(s), current is <b>{}</>",
+                    os.join(", "),
+                    env::consts::OS
+                ));
+            }
+        }
+        if let Some(arch) = &self.arch {
+            if !arch.iter().any(|arch| arch == env::consts::ARCH) {
+                problems.push(format!(
+                    "Only supports <b>{}</> architecture(s), current is <b>{}</>",
+                    arch.join(", "),
+                    env::consts::ARCH
+                ));
+            }
+        }
+        for (runtime, req) in &self.runtimes {
+            let Some(command) = runtime_command(runtime) else {
+                warn!("Filter <filter>{name}</> requires unknown runtime <b>{runtime}</>, skipping check");
+                continue;
+            };
+            match runtime_version(runtime, command) {
+                Some(version) if req.matches(&version) => {}
+                Some(version) => problems.push(format!(
+                    "Requires {runtime} <b>{req}</>, installed version is <b>{version}</>"
+                )),
+                None => problems.push(format!(
+                    "Requires {runtime} <b>{req}</>, but it is not installed"
+                )),
+            }
+        }
+        if problems.is_empty() {
+            return Ok(());
+        }
+        bail!(
+            "Filter <filter>{name}</> is not compatible with this system\n{}",
+            format_problems(&problems)
+        )
+    }
+}
+
+/// Program and argument that print the version of a known runtime.
+fn runtime_command(runtime: &str) -> Option<(String, &'static str)> {
+    match runtime {
+        "node" | "nodejs" => Some((UserConfig::nodejs_runtime(), "--version")),
+        "python" => Some((UserConfig::python_command(), "--version")),
+        "deno" | "bun" => Some((runtime.to_owned(), "--version")),
+        "go" => Some(("go".to_owned(), "version")),
+        _ => None,
+    }
+}
+
+/// Installed version of a runtime, or `None` if it can't be found. Results are cached for the
+/// lifetime of the process.
+fn runtime_version(runtime: &str, (program, arg): (String, &str)) -> Option<Version> {
+    if let Some(version) = RUNTIME_VERSIONS.get(runtime) {
+        return version.value().to_owned();
+    }
+    let version = process::Command::new(program)
+        .arg(arg)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| {
+            // Some older Python versions print the version to stderr
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            parse_version(&stdout).or_else(|| parse_version(&stderr))
+        });
+    RUNTIME_VERSIONS.insert(runtime.to_owned(), version.to_owned());
+    version
+}
+
+/// Find the first version-like token, e.g. `v18.17.0`, `Python 3.11.7` or `go1.22.1`.
+fn parse_version(output: &str) -> Option<Version> {
+    let start = output.find(|c: char| c.is_ascii_digit())?;
+    let version: String = output[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let mut parts: Vec<_> = version.split('.').filter(|part| !part.is_empty()).collect();
+    parts.resize(3, "0");
+    let parts: Vec<u64> = parts[..3]
+        .iter()
+        .filter_map(|part| part.parse().ok())
+        .collect();
+    match parts[..] {
+        [major, minor, patch] => Some(Version::new(major, minor, patch)),
+        _ => None,
+    }
+}
@@ -1,12 +1,19 @@
-use super::{get_resolver_cache_dir, Subprocess, UserConfig};
-use crate::debug;
-use crate::fs::{empty_dir, is_dir_empty, read_json, set_modified_time};
+use super::{
+    auth_token, ensure_online, get_cache_dir, get_repo_cache_dir, get_resolver_cache_dir,
+    git_cache_path, git_clone_url, git_fetch, git_ls_remote, git_read_file, is_offline,
+    parse_version_range, url_host, RemoteRef, UserConfig, INSTALL_INFO,
+};
+use crate::fs::{read_json, set_modified_time, write_file};
+use crate::{debug, warn};
 use anyhow::{bail, Context, Result};
 use once_cell::sync::OnceCell;
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::SystemTime;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use url::Url;
 
 #[derive(Default, Serialize, Deserialize)]
 pub struct Resolver {
@@ -14,9 +21,17 @@ pub struct Resolver {
 }
 
 #[derive(Serialize, Deserialize)]
-struct ResolverData {
-    url: String,
+pub struct ResolverData {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
     versions: Option<Vec<String>>,
+    /// Archive URL template, `{name}`, `{version}` and `{tag}` are replaced
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archive: Option<String>,
+    /// Archive checksums by version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checksums: Option<HashMap<String, String>>,
 }
 
 impl Resolver {
@@ -32,6 +47,70 @@ impl Resolver {
         Self::get(name).map(|data| data.url.to_owned())
     }
 
+    pub fn description(name: &str) -> Option<String> {
+        Self::get(name).ok()?.description.to_owned()
+    }
+
+    /// Filters whose name or description contains `term`, ignoring case. Sorted by name.
+    pub fn search(term: &str) -> Result<Vec<(&'static str, &'static ResolverData)>> {
+        let term = term.to_lowercase();
+        let resolver = get_resolver().context("Failed to load filter resolver")?;
+        let mut results: Vec<_> = resolver
+            .filters
+            .iter()
+            .filter(|(name, data)| {
+                name.to_lowercase().contains(&term)
+                    || data
+                        .description
+                        .as_ref()
+                        .is_some_and(|description| description.to_lowercase().contains(&term))
+            })
+            .map(|(name, data)| (name.as_str(), data))
+            .collect();
+        results.sort_by_key(|(name, _)| *name);
+        Ok(results)
+    }
+
+    /// Released versions of a filter, oldest first. Uses the resolver's version list if it has
+    /// one, otherwise the `<name>-<version>` tags of the repository.
+    pub fn versions(name: &str, url: &str) -> Result<Vec<Version>> {
+        let mut versions: Vec<Version> = match Self::get(name) {
+            Ok(data) if data.url == url && data.versions.is_some() => data
+                .versions
+                .iter()
+                .flatten()
+                .filter_map(|version| Version::parse(version).ok())
+                .collect(),
+            _ => tag_versions(name, &git_ls_remote(&git_clone_url(url))?).collect(),
+        };
+        versions.sort();
+        Ok(versions)
+    }
+
+    /// Archive URL and checksum for a filter version, if the resolver provides an archive.
+    pub fn resolve_archive(
+        name: &str,
+        url: &str,
+        version: &str,
+    ) -> Option<(String, Option<String>)> {
+        let data = Self::get(name).ok()?;
+        if data.url != url {
+            return None;
+        }
+        let archive_url = data
+            .archive
+            .as_ref()?
+            .replace("{name}", name)
+            .replace("{version}", version)
+            .replace("{tag}", &format!("{name}-{version}"));
+        let checksum = data
+            .checksums
+            .as_ref()
+            .and_then(|checksums| checksums.get(version))
+            .cloned();
+        Some((archive_url, checksum))
+    }
+
     pub fn resolve_version(name: &str, url: &str, version_arg: Option<String>) -> Result<String> {
         // Try to get version from resolver
         let get_version = || -> Option<String> {
@@ -43,65 +122,58 @@ impl Resolver {
             versions.sort_by_key(|v| Version::parse(v).ok());
             match &version_arg {
                 Some(arg) if versions[1..].contains(arg) => Some(arg.to_owned()),
+                Some(arg) => {
+                    let range = parse_version_range(arg)?;
+                    versions[1..]
+                        .iter()
+                        .rfind(|v| Version::parse(v).is_ok_and(|v| range.matches(&v)))
+                        .cloned()
+                }
                 None => versions[1..].last().cloned(),
-                _ => None,
             }
         };
         if let Some(version) = get_version() {
             return Ok(version);
         }
+        if is_offline() {
+            debug!("Using installed filters to resolve version");
+            if let Some(version) = installed_version(name, url, version_arg.as_deref())? {
+                return Ok(version);
+            }
+            ensure_online(&format!(
+                "Version <b>{}</> of filter <filter>{name}</>",
+                version_arg.as_deref().unwrap_or("latest")
+            ))?;
+        }
         debug!("Using `git ls-remote` to resolve version");
-        let https_url = format!("https://{url}");
         let version_arg = version_arg.as_deref();
+        let refs = git_ls_remote(&git_clone_url(url)).with_context(|| {
+            format!("Failed to resolve version from `{url}`. Is the url correct?")
+        })?;
         // Check if version is available in git tags
         if let Ok(version) = Version::parse(version_arg.unwrap_or_default()) {
-            let tag = format!("{name}-{version}");
-            let output = Subprocess::new("git")
-                .args(["ls-remote", &https_url, &tag])
-                .run_silent()
-                .with_context(|| {
-                    format!("Failed to check version from `{url}`. Is the url correct?")
-                })?;
-            let output = String::from_utf8(output.stdout)?;
-            if output.split('\n').any(|line| line.ends_with(&tag)) {
+            let tag = format!("refs/tags/{name}-{version}");
+            if refs.iter().any(|r| r.name == tag) {
+                return Ok(version.to_string());
+            }
+        }
+        if let Some(range) = version_arg.and_then(parse_version_range) {
+            let latest = tag_versions(name, &refs)
+                .filter(|version| range.matches(version))
+                .max();
+            if let Some(version) = latest {
                 return Ok(version.to_string());
             }
         }
         if version_arg.is_none() || version_arg == Some("latest") {
-            let output = Subprocess::new("git")
-                .args(["ls-remote", "--tags", &https_url])
-                .run_silent()
-                .with_context(|| {
-                    format!("Failed to get latest version from `{url}`. Is the url correct?")
-                })?;
-            let output = String::from_utf8(output.stdout)?;
-            let mut versions: Vec<Version> = output
-                .split('\n')
-                .filter_map(|line| {
-                    line.split(&format!("refs/tags/{name}-"))
-                        .last()
-                        .and_then(|version| Version::parse(version).ok())
-                })
-                .collect();
-            versions.sort();
-            if let Some(version) = versions.last() {
+            let latest = tag_versions(name, &refs).max();
+            if let Some(version) = latest {
                 return Ok(version.to_string());
             }
         }
         if version_arg.is_none() || version_arg == Some("HEAD") {
-            let output = Subprocess::new("git")
-                .args(["ls-remote", "--symref", &https_url, "HEAD"])
-                .run_silent()
-                .with_context(|| {
-                    format!("Failed to get HEAD version from `{url}`. Is the url correct?")
-                })?;
-            let output = String::from_utf8(output.stdout)?;
-            let sha = output
-                .split('\n')
-                .nth(1)
-                .and_then(|line| line.split('\t').next());
-            if let Some(sha) = sha {
-                return Ok(sha.to_owned());
+            if let Some(head) = refs.iter().find(|r| r.name == "HEAD") {
+                return Ok(head.oid.to_string());
             }
         }
         bail!(
@@ -114,45 +186,216 @@ impl Resolver {
     }
 }
 
+/// Versions of a filter released as `<name>-<version>` tags.
+fn tag_versions<'a>(name: &str, refs: &'a [RemoteRef]) -> impl Iterator<Item = Version> + 'a {
+    let prefix = format!("refs/tags/{name}-");
+    refs.iter().filter_map(move |r| {
+        r.name
+            .strip_prefix(&prefix)
+            .and_then(|version| Version::parse(version).ok())
+    })
+}
+
+/// Highest installed version of a filter matching `version_arg`, used to resolve versions offline.
+fn installed_version(name: &str, url: &str, version_arg: Option<&str>) -> Result<Option<String>> {
+    let filter_dir = get_cache_dir()?
+        .join("filters")
+        .join(git_cache_path(url))
+        .join(name);
+    let Ok(entries) = fs::read_dir(filter_dir) else {
+        return Ok(None);
+    };
+    let range = version_arg.and_then(parse_version_range);
+    let version = entries
+        .flatten()
+        .filter(|entry| entry.path().join(INSTALL_INFO).is_file())
+        .filter_map(|entry| Version::parse(&entry.file_name().to_string_lossy()).ok())
+        .filter(|version| match (&range, version_arg) {
+            (Some(range), _) => range.matches(version),
+            (None, None | Some("latest")) => true,
+            (None, Some(arg)) => version.to_string() == arg,
+        })
+        .max();
+    Ok(version.map(|version| version.to_string()))
+}
+
 fn get_resolver() -> Result<&'static Resolver> {
     static RESOLVER: OnceCell<Resolver> = OnceCell::new();
     RESOLVER.get_or_try_init(|| {
         let mut resolver = Resolver::default();
         for resolver_url in UserConfig::resolvers() {
-            let (url, path) = parse_resolver_url(&resolver_url)
+            let source = ResolverSource::parse(&resolver_url)
                 .with_context(|| format!("Failed to parse url `{resolver_url}`",))?;
-            let resolver_dir = get_resolver_cache_dir()?.join(&url);
-            let resolver_file = resolver_dir.join(&path);
-            let https_url = format!("https://{url}");
-            if is_dir_empty(&resolver_dir)? {
-                empty_dir(&resolver_dir)?;
-                Subprocess::new("git")
-                    .args(["clone", &https_url, "."])
-                    .current_dir(&resolver_dir)
-                    .run_silent()
-                    .with_context(|| format!("Failed to clone `{https_url}`"))?;
-            } else {
-                let last_modified = resolver_file.metadata()?.modified()?.elapsed()?.as_secs();
-                if last_modified > UserConfig::resolver_update_interval() {
-                    Subprocess::new("git")
-                        .args(["pull"])
-                        .current_dir(&resolver_dir)
-                        .run_silent()
-                        .with_context(|| format!("Failed to pull `{https_url}`"))?;
-                    set_modified_time(&resolver_file, SystemTime::now())?;
-                }
-            }
-            let data = read_json::<Resolver>(resolver_file)?;
-            resolver.filters.extend(data.filters)
+            let data = source
+                .load(&resolver_url)
+                .with_context(|| format!("Failed to load resolver `{resolver_url}`"))?;
+            // Resolvers listed later take precedence
+            resolver.filters.extend(data.filters);
         }
         Ok(resolver)
     })
 }
 
+/// Where a resolver file is loaded from.
+pub enum ResolverSource {
+    /// A file in a git repository
+    Git { url: String, path: String },
+    /// A file served over HTTP(S)
+    Http(String),
+    /// A file on disk, e.g. on a shared network drive
+    File(PathBuf),
+}
+
+impl ResolverSource {
+    /// - `<git-url>//<path>` or `github.com/<user>/<repo>/<path>`: a file in a git repository
+    /// - `https://<host>/<path>`: a file served over HTTP(S)
+    /// - `file:///<path>` or an absolute path: a local file
+    pub fn parse(url: &str) -> Result<Self> {
+        match url.split_once("://") {
+            None if Path::new(url).is_absolute() => return Ok(Self::File(PathBuf::from(url))),
+            Some((scheme, rest)) if !rest.contains("//") => match scheme {
+                "http" | "https" => return Ok(Self::Http(url.to_owned())),
+                "file" => {
+                    let path = Url::parse(url)
+                        .ok()
+                        .and_then(|url| url.to_file_path().ok())
+                        .context("Invalid file URL")?;
+                    return Ok(Self::File(path));
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        let (url, path) = parse_resolver_url(url)?;
+        Ok(Self::Git { url, path })
+    }
+
+    fn load(&self, resolver_url: &str) -> Result<Resolver> {
+        match self {
+            Self::Git { url, path } => load_git(resolver_url, url, path),
+            Self::Http(url) => load_http(url),
+            Self::File(path) => read_json(path),
+        }
+    }
+}
+
+/// Whether a cached resolver file should be refreshed.
+fn is_outdated(resolver_file: &Path) -> Result<bool> {
+    match resolver_file.metadata() {
+        Ok(metadata) => {
+            Ok(metadata.modified()?.elapsed()?.as_secs() > UserConfig::resolver_update_interval())
+        }
+        Err(_) => Ok(true),
+    }
+}
+
+fn load_git(resolver_url: &str, url: &str, path: &str) -> Result<Resolver> {
+    let resolver_dir = get_resolver_cache_dir()?.join(git_cache_path(url));
+    let resolver_file = resolver_dir.join(path);
+    let clone_url = git_clone_url(url);
+    let outdated = is_outdated(&resolver_file)?;
+    if outdated && is_offline() && resolver_file.is_file() {
+        debug!("Using cached resolver `{resolver_url}`");
+    } else if outdated {
+        ensure_online(&format!("Resolver `{resolver_url}`"))?;
+        let repo_dir = get_repo_cache_dir()?.join(git_cache_path(url));
+        let repo = git_fetch(&clone_url, &repo_dir)?;
+        let data = git_read_file(&repo, "refs/remotes/origin/HEAD", path)
+            .with_context(|| format!("Failed to read `{path}` from `{clone_url}`"))?;
+        if let Some(parent) = resolver_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_file(&resolver_file, data)?;
+        set_modified_time(&resolver_file, SystemTime::now())?;
+    }
+    read_json::<Resolver>(resolver_file)
+}
+
+/// Load a resolver served over HTTP. The response is cached and revalidated with its `ETag`
+/// once the update interval has passed. The cached copy is used if the server can't be reached.
+fn load_http(url: &str) -> Result<Resolver> {
+    let resolver_file = get_resolver_cache_dir()?
+        .join("http")
+        .join(git_cache_path(url));
+    let mut etag_file = resolver_file.clone().into_os_string();
+    etag_file.push(".etag");
+    let etag_file = PathBuf::from(etag_file);
+    let outdated = is_outdated(&resolver_file)?;
+    if outdated && is_offline() && resolver_file.is_file() {
+        debug!("Using cached resolver `{url}`");
+    } else if outdated {
+        ensure_online(&format!("Resolver `{url}`"))?;
+        let etag = fs::read_to_string(&etag_file)
+            .ok()
+            .filter(|_| resolver_file.is_file());
+        match fetch_http(url, etag.as_deref()) {
+            Ok(Some((data, etag))) => {
+                if let Some(parent) = resolver_file.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                write_file(&resolver_file, data)?;
+                match etag {
+                    Some(etag) => write_file(&etag_file, etag)?,
+                    None => {
+                        let _ = fs::remove_file(&etag_file);
+                    }
+                }
+            }
+            Ok(None) => debug!("Resolver `{url}` is up to date"),
+            Err(e) if resolver_file.is_file() => {
+                warn!("Failed to update resolver `{url}`, using the cached version");
+                debug!("{e}");
+            }
+            Err(e) => return Err(e),
+        }
+        set_modified_time(&resolver_file, SystemTime::now())?;
+    }
+    read_json::<Resolver>(resolver_file)
+}
+
+/// Download a resolver file. Returns `None` if it wasn't modified since `etag`, otherwise the
+/// data and its new `ETag`.
+fn fetch_http(url: &str, etag: Option<&str>) -> Result<Option<(String, Option<String>)>> {
+    let mut request = ureq::get(url).timeout(Duration::from_secs(30));
+    if let Some(token) = url_host(url).and_then(|host| auth_token(&host)) {
+        request = request.set("Authorization", &format!("Bearer {token}"));
+    }
+    if let Some(etag) = etag {
+        request = request.set("If-None-Match", etag);
+    }
+    let response = request.call().context("Failed to download resolver")?;
+    if response.status() == 304 {
+        return Ok(None);
+    }
+    let etag = response.header("ETag").map(str::to_owned);
+    let data = response
+        .into_string()
+        .context("Failed to download resolver")?;
+    serde_json::from_str::<Resolver>(&data).context("Invalid resolver file")?;
+    Ok(Some((data, etag)))
+}
+
+/// Split a resolver URL into the repository URL and the file path. The file path is separated
+/// with `//`, e.g. `https://gitlab.com/group/repo.git//resolver.json`. For backward
+/// compatibility, `github.com/<user>/<repo>/<path>` is also accepted.
 fn parse_resolver_url(url: &str) -> Result<(String, String)> {
+    let (scheme, rest) = match url.split_once("://") {
+        Some((scheme, rest)) => (Some(scheme), rest),
+        None => (None, url),
+    };
+    if let Some((repo, path)) = rest.split_once("//") {
+        let repo_url = match scheme {
+            Some(scheme) => format!("{scheme}://{repo}"),
+            None => repo.to_owned(),
+        };
+        return Ok((repo_url, path.to_owned()));
+    }
     let url_parts: Vec<&str> = url.split('/').collect();
-    if url.starts_with("https://") || url_parts.len() < 4 {
-        bail!("Incorrect URL format. Expected: `github.com/<user>/<repo>/<resolver-file-path>`");
+    if scheme.is_some() || url_parts.len() < 4 {
+        bail!(
+            "Incorrect URL format. Expected: `github.com/<user>/<repo>/<resolver-file-path>` \
+             or `<git-url>//<resolver-file-path>`"
+        );
     }
     let repo_url = url_parts[0..3].join("/");
     let path = url_parts[3..].join("/");
@@ -1,4 +1,4 @@
-use super::{Config, Export, ExportPaths, Temp};
+use super::{take_warning_count, Config, Export, ExportPaths, Temp};
 use crate::fs::{rimraf, symlink, sync_dir};
 use crate::{debug, info, measure_time};
 use anyhow::{Context, Result};
@@ -7,6 +7,7 @@ use url::Url;
 
 pub async fn runner(config: &Config, profile_name: &str, clean: bool, compat: bool) -> Result<()> {
     let start = Instant::now();
+    take_warning_count();
     let bp = config.get_behavior_pack();
     let rp = config.get_resource_pack();
     let data = config.get_data_path();
@@ -104,6 +105,10 @@ pub async fn runner(config: &Config, profile_name: &str, clean: bool, compat: bo
     });
 
     info!("Successfully ran the <profile>{profile_name}</> profile");
-    info!("<green>Finished</> in {}ms", start.elapsed().as_millis());
+    let elapsed = start.elapsed().as_millis();
+    match take_warning_count() {
+        0 => info!("<green>Finished</> in {elapsed}ms"),
+        count => info!("<green>Finished</> in {elapsed}ms with <yellow>{count} warning(s)</>"),
+    }
     Ok(())
 }
@@ -0,0 +1,165 @@
+use super::FilterContext;
+use anyhow::Result;
+use std::path::Path;
+use std::process;
+
+/// Filesystem and network restrictions applied to remote filter subprocesses.
+///
+/// Sandboxed filters can only write inside the temp directory and their own cache directory
+/// (`.regolith/cache/<filter>`, also exposed as `TMPDIR`). Besides those, they can read their
+/// own files (`FILTER_DIR`), system directories and the installation of the program running
+/// them, e.g. a Python or Node.js runtime. Network access is blocked unless the filter sets
+/// `network` in its `filter.json`.
+#[cfg(target_os = "linux")]
+pub struct Sandbox {
+    ruleset: landlock::RulesetCreated,
+    cache_dir: std::path::PathBuf,
+}
+
+#[cfg(target_os = "linux")]
+impl Sandbox {
+    pub fn new(context: &FilterContext, temp: &Path) -> Result<Option<Self>> {
+        use super::temp_write_dirs;
+        use anyhow::Context;
+        use landlock::{
+            path_beneath_rules, Access, AccessFs, AccessNet, CompatLevel, Compatible, Ruleset,
+            RulesetAttr, RulesetCreatedAttr, ABI,
+        };
+
+        let cache_dir = context.cache_dir()?;
+        let mut writable = temp_write_dirs(temp)?;
+        writable.push(cache_dir.to_owned());
+        writable.push("/dev/null".into());
+        let mut readable = vec![context.filter_dir.to_owned()];
+        readable.extend(
+            SYSTEM_DIRS
+                .iter()
+                .map(std::path::PathBuf::from)
+                .filter(|path| path.exists()),
+        );
+
+        let network = context
+            .remote_config
+            .as_ref()
+            .is_some_and(|config| config.network);
+        let inner = || -> Result<landlock::RulesetCreated> {
+            let mut ruleset = Ruleset::default()
+                .set_compatibility(CompatLevel::HardRequirement)
+                .handle_access(AccessFs::from_all(ABI::V1))?
+                .set_compatibility(CompatLevel::BestEffort)
+                .handle_access(AccessFs::from_all(ABI::V5))?;
+            if !network {
+                ruleset = ruleset
+                    .set_compatibility(CompatLevel::HardRequirement)
+                    .handle_access(AccessNet::from_all(ABI::V4))
+                    .context("Blocking network access requires Linux 6.7 or newer")?;
+            }
+            let ruleset = ruleset
+                .create()?
+                .add_rules(path_beneath_rules(&readable, AccessFs::from_read(ABI::V5)))?
+                .add_rules(path_beneath_rules(&writable, AccessFs::from_all(ABI::V5)))?;
+            Ok(ruleset)
+        };
+        let ruleset = inner().context("Sandbox is not supported on this system")?;
+        Ok(Some(Self { ruleset, cache_dir }))
+    }
+
+    pub fn apply(self, command: &mut process::Command) -> Result<()> {
+        use landlock::{path_beneath_rules, AccessFs, RulesetCreatedAttr, ABI};
+        use std::io;
+        use std::os::unix::process::CommandExt;
+
+        let mut ruleset = self.ruleset;
+        if let Some(prefix) = program_prefix(command.get_program()) {
+            ruleset =
+                ruleset.add_rules(path_beneath_rules([prefix], AccessFs::from_read(ABI::V5)))?;
+        }
+        command.env("TMPDIR", &self.cache_dir);
+        // SAFETY: only async-signal-safe syscalls (dup, prctl, landlock_restrict_self) are made
+        // between fork and exec.
+        unsafe {
+            command.pre_exec(move || {
+                ruleset
+                    .try_clone()?
+                    .restrict_self()
+                    .map(|_| ())
+                    .map_err(|_| io::Error::from(io::ErrorKind::PermissionDenied))
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Directories with the shared libraries and configuration runtimes need.
+#[cfg(target_os = "linux")]
+const SYSTEM_DIRS: [&str; 11] = [
+    "/bin", "/dev", "/etc", "/lib", "/lib32", "/lib64", "/nix", "/opt", "/proc", "/sbin", "/usr",
+];
+
+/// Installation directory of a program, e.g. `~/.pyenv/versions/3.12` for
+/// `~/.pyenv/versions/3.12/bin/python`, so interpreters can load their standard library.
+#[cfg(target_os = "linux")]
+fn program_prefix(program: &std::ffi::OsStr) -> Option<std::path::PathBuf> {
+    use std::{env, path::PathBuf};
+
+    let program = PathBuf::from(program);
+    let path = match program.components().count() {
+        1 => env::split_paths(&env::var_os("PATH")?)
+            .map(|dir| dir.join(&program))
+            .find(|path| path.is_file())?,
+        _ => program,
+    };
+    let dir = dunce::canonicalize(path).ok()?.parent()?.to_owned();
+    let prefix = match dir.file_name()?.to_str()? {
+        "bin" | "sbin" | "shims" => dir.parent()?.to_owned(),
+        _ => dir.to_owned(),
+    };
+    // Never expose the whole home directory, e.g. for `~/bin/python`
+    let home = env::var_os("HOME").map(PathBuf::from);
+    match prefix.parent().is_none() || Some(&prefix) == home.as_ref() {
+        true => Some(dir),
+        false => Some(prefix),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub struct Sandbox;
+
+#[cfg(not(target_os = "linux"))]
+impl Sandbox {
+    pub fn new(_context: &FilterContext, _temp: &Path) -> Result<Option<Self>> {
+        use std::sync::Once;
+        static WARNING: Once = Once::new();
+        WARNING.call_once(|| crate::warn!("Sandboxing filters is only supported on Linux"));
+        Ok(None)
+    }
+
+    pub fn apply(self, _command: &mut process::Command) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Find the path from a "permission denied" error printed by the filter.
+pub fn find_denied_path(line: &str) -> Option<String> {
+    let lower = line.to_lowercase();
+    let index = ["permission denied", "eacces", "operation not permitted"]
+        .iter()
+        .find_map(|pattern| lower.find(pattern))?;
+    // Node.js: "EACCES: permission denied, open '/path'"
+    // Python: "PermissionError: [Errno 13] Permission denied: '/path'"
+    for quote in ['\'', '"'] {
+        let mut parts = line.rsplit(quote);
+        if let (Some(_), Some(path), Some(_)) = (parts.next(), parts.next(), parts.next()) {
+            if path.contains(['/', '\\']) {
+                return Some(path.to_owned());
+            }
+        }
+    }
+    // Go: "open /path: permission denied"
+    line.get(..index)?
+        .trim_end_matches([':', ' '])
+        .rsplit(' ')
+        .next()
+        .filter(|path| path.starts_with('/'))
+        .map(|path| path.to_owned())
+}
@@ -1,4 +1,5 @@
-use super::get_current_dir;
+use super::{find_denied_path, get_current_dir, MessageHandler, Sandbox, UserConfig};
+use crate::warn;
 use anyhow::{anyhow, bail, Context, Result};
 use std::{
     ffi::OsStr,
@@ -9,6 +10,9 @@ use std::{
 
 pub struct Subprocess {
     command: process::Command,
+    /// Applied when the process is spawned
+    sandbox: Option<Sandbox>,
+    sandboxed: bool,
 }
 
 impl Subprocess {
@@ -18,6 +22,8 @@ impl Subprocess {
     {
         Self {
             command: process::Command::new(command),
+            sandbox: None,
+            sandboxed: false,
         }
     }
 
@@ -43,12 +49,31 @@ impl Subprocess {
         self
     }
 
+    pub fn env(&mut self, key: impl AsRef<OsStr>, value: impl AsRef<OsStr>) -> &mut Self {
+        self.command.env(key, value);
+        self
+    }
+
     pub fn setup_env(&mut self, filter_dir: impl AsRef<Path>) -> &mut Self {
         self.command.env("FILTER_DIR", filter_dir.as_ref());
         self
     }
 
+    pub fn sandbox(&mut self, sandbox: Option<Sandbox>) -> &mut Self {
+        self.sandboxed = sandbox.is_some();
+        self.sandbox = sandbox;
+        self
+    }
+
+    fn apply_sandbox(&mut self) -> Result<()> {
+        if let Some(sandbox) = self.sandbox.take() {
+            sandbox.apply(&mut self.command)?;
+        }
+        Ok(())
+    }
+
     pub fn run(&mut self) -> Result<process::Output> {
+        self.apply_sandbox()?;
         let output = self
             .command
             .env("ROOT_DIR", get_current_dir()?)
@@ -66,12 +91,39 @@ impl Subprocess {
         Ok(output)
     }
 
-    pub fn run_with_prefix(&mut self, prefix: &str) -> Result<process::Output> {
+    /// Spawns the process with piped stdio, for processes that are communicated with while
+    /// running.
+    pub fn spawn(&mut self) -> Result<process::Child> {
+        self.apply_sandbox()?;
+        self.command
+            .env("ROOT_DIR", get_current_dir()?)
+            .stdin(process::Stdio::piped())
+            .stdout(process::Stdio::piped())
+            .stderr(process::Stdio::piped())
+            .spawn()
+            .map_err(|err| match err.kind() {
+                io::ErrorKind::NotFound => self.program_not_found_error(),
+                _ => anyhow!(err),
+            })
+            .context("Failed spawning subprocess")
+    }
+
+    /// Runs a filter, rendering the messages it reports through the rgl protocol. Only stdout is
+    /// piped to find the messages, stderr is inherited so the filter keeps colors and progress
+    /// bars, unless it has to be prefixed or checked for sandbox errors.
+    pub fn run_filter(&mut self, name: &str) -> Result<()> {
+        self.apply_sandbox()?;
+        let prefix = UserConfig::subprocess_logging().then_some(name);
+        let sandboxed = self.sandboxed;
+        let stderr = match prefix.is_some() || sandboxed {
+            true => process::Stdio::piped(),
+            false => process::Stdio::inherit(),
+        };
         let mut child = self
             .command
             .env("ROOT_DIR", get_current_dir()?)
             .stdout(process::Stdio::piped())
-            .stderr(process::Stdio::piped())
+            .stderr(stderr)
             .spawn()
             .map_err(|err| match err.kind() {
                 io::ErrorKind::NotFound => self.program_not_found_error(),
@@ -80,58 +132,63 @@ impl Subprocess {
             .context("Failed spawning subprocess")?;
 
         let stdout = child.stdout.take().expect("stdout piped");
-        let stderr = child.stderr.take().expect("stderr piped");
-        let prefix_out = prefix.to_owned();
-        let prefix_err = prefix.to_owned();
+        let prefix_out = prefix.map(|prefix| prefix.to_owned());
+        let prefix_err = prefix_out.to_owned();
+        let mut handler = MessageHandler::new(name);
 
         let stdout_thread = thread::spawn(move || {
+            let mut denied_path = None;
             BufReader::new(stdout).lines().for_each(|line| {
                 if let Ok(line) = line {
-                    crate::logger::Logger::info(format!("[{prefix_out}] {line}"));
+                    if handler.handle(&line) {
+                        return;
+                    }
+                    if sandboxed && denied_path.is_none() {
+                        denied_path = find_denied_path(&line);
+                    }
+                    print_output(prefix_out.as_deref(), &line, false);
                 }
             });
+            (handler.error_count, denied_path)
         });
-        let stderr_thread = thread::spawn(move || {
-            BufReader::new(stderr).lines().for_each(|line| {
-                if let Ok(line) = line {
-                    crate::logger::Logger::info(format!("[{prefix_err}] {line}"));
-                }
-            });
+        let stderr_thread = child.stderr.take().map(|stderr| {
+            thread::spawn(move || {
+                let mut denied_path = None;
+                BufReader::new(stderr).lines().for_each(|line| {
+                    if let Ok(line) = line {
+                        if sandboxed && denied_path.is_none() {
+                            denied_path = find_denied_path(&line);
+                        }
+                        print_output(prefix_err.as_deref(), &line, true);
+                    }
+                });
+                denied_path
+            })
         });
 
         let status = child.wait().context("Failed running subprocess")?;
-        stdout_thread.join().ok();
-        stderr_thread.join().ok();
+        let (error_count, stdout_denied) = stdout_thread.join().unwrap_or_default();
+        let denied_path = stderr_thread
+            .and_then(|thread| thread.join().ok().flatten())
+            .or(stdout_denied);
 
-        if !status.success() {
-            bail!("Process exited with non-zero status code");
+        let hint = "Sandboxed filters can only write inside the temp directory and their cache \
+                    directory, and read their own files and system directories";
+        match (status.success(), denied_path) {
+            (false, Some(path)) => {
+                bail!("Sandbox denied access to <b>{path}</>\n<yellow> >></> {hint}")
+            }
+            (false, None) => bail!("Process exited with non-zero status code"),
+            // The filter handled the error itself, but its output may be incomplete
+            (true, Some(path)) => warn!(
+                "Sandbox denied filter <filter>{name}</> access to <b>{path}</>\n<yellow> >></> {hint}"
+            ),
+            (true, None) => {}
         }
-        Ok(process::Output {
-            status,
-            stdout: vec![],
-            stderr: vec![],
-        })
-    }
-
-    pub fn run_silent(&mut self) -> Result<process::Output> {
-        let output = self
-            .command
-            .env("ROOT_DIR", get_current_dir()?)
-            .stderr(process::Stdio::piped())
-            .stdout(process::Stdio::piped())
-            .spawn()
-            .map_err(|err| match err.kind() {
-                io::ErrorKind::NotFound => self.program_not_found_error(),
-                _ => anyhow!(err),
-            })
-            .context("Failed spawning subprocess")?
-            .wait_with_output()
-            .context("Failed running subprocess")?;
-        if !output.status.success() {
-            println!("{}", String::from_utf8_lossy(&output.stderr));
-            bail!("Process exited with non-zero status code");
+        if error_count > 0 {
+            bail!("Filter reported {error_count} error(s)");
         }
-        Ok(output)
+        Ok(())
     }
 
     fn program_not_found_error(&self) -> anyhow::Error {
@@ -140,7 +197,6 @@ impl Subprocess {
         let install_link = match program.to_str() {
             Some("bun") => Some("https://bun.sh/docs/installation"),
             Some("deno") => Some("https://docs.deno.com/runtime/#install-deno"),
-            Some("git") => Some("https://git-scm.com/downloads"),
             Some("go") => Some("https://go.dev/doc/install"),
             Some("node") => Some("https://nodejs.org/en/download/prebuilt-installer"),
             Some("python") => Some("https://www.python.org/downloads"),
@@ -152,3 +208,13 @@ impl Subprocess {
         anyhow!(message)
     }
 }
+
+/// Prints a line of subprocess output, prefixed with the filter name when subprocess logging is
+/// enabled.
+pub fn print_output(prefix: Option<&str>, line: &str, is_stderr: bool) {
+    match prefix {
+        Some(prefix) => crate::logger::Logger::info(format!("[{prefix}] {line}")),
+        None if is_stderr => eprintln!("{line}"),
+        None => println!("{line}"),
+    }
+}
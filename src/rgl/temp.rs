@@ -1,4 +1,5 @@
-use std::path::PathBuf;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
 
 pub struct Temp {
     pub bp: PathBuf,
@@ -19,3 +20,17 @@ impl Temp {
         }
     }
 }
+
+/// The temp directory and the export targets its BP and RP may be symlinks to, for filters that
+/// are only allowed to write to the temp directory.
+pub fn temp_write_dirs(temp: &Path) -> Result<Vec<PathBuf>> {
+    let temp = dunce::canonicalize(temp)?;
+    let mut dirs = vec![temp.to_owned()];
+    for pack in ["BP", "RP"] {
+        let path = temp.join(pack);
+        if path.is_symlink() {
+            dirs.push(dunce::canonicalize(path)?);
+        }
+    }
+    Ok(dirs)
+}
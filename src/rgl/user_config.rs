@@ -2,12 +2,15 @@ use super::get_user_config_path;
 use crate::fs::{read_json, write_json};
 use crate::warn;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::OnceLock;
 
 #[derive(Serialize, Deserialize)]
 pub struct UserConfig {
     #[serde(default = "default_username")]
     pub username: String,
+    /// Git, HTTP(S) or local resolver files. When filter names collide, the resolver listed last
+    /// is used.
     #[serde(default = "default_resolvers")]
     pub resolvers: Vec<String>,
     #[serde(default = "default_resolver_update_interval")]
@@ -18,10 +21,15 @@ pub struct UserConfig {
     pub force_compat: bool,
     #[serde(default)]
     pub subprocess_logging: bool,
+    #[serde(default)]
+    pub sandbox: bool,
     pub mojang_dir: Option<String>,
     pub nodejs_runtime: Option<String>,
     pub nodejs_package_manager: Option<String>,
     pub python_command: Option<String>,
+    /// Access tokens for private repositories by host, e.g. `{ "github.com": "<token>" }`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub auth_tokens: HashMap<String, String>,
 }
 
 impl UserConfig {
@@ -33,10 +41,12 @@ impl UserConfig {
             websocket_port: default_websocket_port(),
             force_compat: false,
             subprocess_logging: false,
+            sandbox: false,
             mojang_dir: None,
             nodejs_runtime: None,
             nodejs_package_manager: None,
             python_command: None,
+            auth_tokens: HashMap::new(),
         }
     }
 
@@ -64,6 +74,10 @@ impl UserConfig {
         get_user_config().subprocess_logging
     }
 
+    pub fn sandbox() -> bool {
+        get_user_config().sandbox
+    }
+
     pub fn mojang_dir() -> Option<String> {
         get_user_config().mojang_dir.to_owned()
     }
@@ -91,6 +105,10 @@ impl UserConfig {
             .to_owned()
             .unwrap_or("python".to_owned())
     }
+
+    pub fn auth_token(host: &str) -> Option<String> {
+        get_user_config().auth_tokens.get(host).cloned()
+    }
 }
 
 fn default_username() -> String {